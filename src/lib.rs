@@ -14,6 +14,10 @@
 //! - Compatible with `#![no_std]` environments.
 //! - Supports concatenation of `OwnedUnicodeString` instances and Rust strings using the `Add` trait.
 //! - Enables comparison between `OwnedUnicodeString` instances using the `PartialEq` trait.
+//! - Provides the `krnlstring!` macro to encode string literals to UTF-16 at compile time.
+//! - Provides `UnicodeStringBuilder` to assemble a string from many parts in a single buffer.
+//! - Provides `OwnedAnsiString`, a companion wrapper for the single-byte `STRING` (`ANSI_STRING`).
+//! - Provides `Utf16Display` to format a borrowed `&[u16]` without copying it.
 //!
 //! ## Usage Example
 //!
@@ -28,6 +32,21 @@
 //! ```
 //!
 //!
+//! ## Cargo Features
+//!
+//! - `serde`: implements `Serialize` and `Deserialize` for `OwnedUnicodeString`, using its UTF-8 representation.
+//! - `std`: implements `std::error::Error` for `UnicodeStringError`.
+//! - `unicode-case`: enables case-insensitive comparison and case conversion using Unicode simple case folding
+//!   and case mappings, at the cost of bundled case tables.
+//! - `width`: enables `display_width` for aligning console output, at the cost of a bundled East Asian width
+//!   table.
+//! - `nt-rtl`: enables `nt_equals`, which compares strings with `RtlEqualUnicodeString` and links against the
+//!   NT runtime library.
+//!
+//! ## Minimum Supported Rust Version
+//!
+//! `KRNLSTRING` requires Rust 1.82 or newer, as declared by `rust-version` in `Cargo.toml`.
+//!
 //! ## Performance
 //!
 //! `KRNLSTRING` is optimized for minimal memory copying and efficient buffer management.
@@ -51,16 +70,108 @@
 
 #![cfg_attr(not(test), no_std)]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "unicode-case")]
+mod case_folding;
+#[cfg(feature = "unicode-case")]
+mod case_mapping;
+#[cfg(feature = "width")]
+mod east_asian_width;
 
 use core::slice;
+use alloc::boxed::Box;
+use alloc::string::String;
 use alloc::vec::Vec;
-use core::char::decode_utf16;
-use core::fmt;
-use core::mem::size_of;
-use core::ops::Add;
-use windows_sys::core::{PCWSTR, PWSTR};
+use core::borrow::Borrow;
+use core::char::{decode_utf16, DecodeUtf16Error};
+use core::cmp::Ordering;
+use core::fmt::{self, Write as _};
+use core::hash::{Hash, Hasher};
+use core::mem::{self, size_of, size_of_val};
+use core::ops::{Add, Index, Range, RangeFrom, RangeFull, RangeTo};
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering as AtomicOrdering};
+use windows_sys::core::{GUID, PCWSTR, PWSTR};
 use windows_sys::Win32::Foundation::UNICODE_STRING;
+use windows_sys::Win32::System::Kernel::STRING;
+
+/// The largest number of bytes a `UNICODE_STRING` or `STRING` can describe.
+///
+/// Both `Length` and `MaximumLength` are `u16` byte counts, so no string, including any terminator covered by
+/// `MaximumLength`, can be longer than this. Operations that would exceed it return
+/// [`UnicodeStringError::TooLong`] or panic, depending on whether they are fallible.
+pub const MAX_BYTES: usize = u16::MAX as usize;
+
+/// The largest number of UTF-16 code units an `OwnedUnicodeString` can hold.
+///
+/// This is [`MAX_BYTES`] divided by the size of a code unit, rounded down, i.e. 32767. A string of exactly this
+/// length is accepted, but leaves no room for a NUL terminator within `MaximumLength`. When such a string is
+/// NUL-terminated, for example by a `PCWSTR` conversion, the terminator is stored past `MaximumLength`, which is
+/// capped at `MAX_CODE_UNITS * 2` bytes.
+pub const MAX_CODE_UNITS: usize = MAX_BYTES / size_of::<u16>();
+
+/// Errors returned by the fallible operations of `OwnedUnicodeString`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeStringError {
+    /// The string would need `bytes` bytes, more than the `u16` length fields of a `UNICODE_STRING` can hold.
+    TooLong {
+        /// The number of bytes the string would have needed.
+        bytes: usize,
+    },
+    /// The code unit at `index` is not part of a well-formed UTF-16 sequence.
+    InvalidUtf16 {
+        /// The code-unit index of the first malformed code unit.
+        index: usize,
+    },
+    /// The string contains a NUL code unit at `index`, before the end of its logical content.
+    EmbeddedNul {
+        /// The code-unit index of the first embedded NUL.
+        index: usize,
+    },
+    /// The input bytes are not valid UTF-8 starting at byte offset `index`.
+    InvalidUtf8 {
+        /// The byte offset of the first invalid UTF-8 sequence.
+        index: usize,
+    },
+    /// A code-unit index was greater than the length of the string.
+    IndexOutOfBounds,
+    /// A code-unit index fell between the high and low halves of a surrogate pair.
+    SplitSurrogate,
+    /// The allocator could not provide the requested capacity, or the capacity overflowed `usize`.
+    AllocFailed,
+}
+
+impl fmt::Display for UnicodeStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnicodeStringError::TooLong { bytes } => write!(
+                f,
+                "string of {} bytes exceeds the UNICODE_STRING limit of {} bytes",
+                bytes, MAX_BYTES
+            ),
+            UnicodeStringError::InvalidUtf16 { index } => {
+                write!(f, "invalid UTF-16 code unit at index {}", index)
+            }
+            UnicodeStringError::EmbeddedNul { index } => {
+                write!(f, "embedded NUL code unit at index {}", index)
+            }
+            UnicodeStringError::InvalidUtf8 { index } => {
+                write!(f, "invalid UTF-8 sequence at byte offset {}", index)
+            }
+            UnicodeStringError::IndexOutOfBounds => f.write_str("code-unit index out of bounds"),
+            UnicodeStringError::SplitSurrogate => f.write_str("code-unit index splits a surrogate pair"),
+            UnicodeStringError::AllocFailed => f.write_str("memory allocation failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnicodeStringError {}
 
+/// A specialized `Result` type for the fallible operations of `OwnedUnicodeString`.
+pub type Result<T> = core::result::Result<T, UnicodeStringError>;
 
 /// A safe wrapper around Windows `UNICODE_STRING` that owns its UTF-16 buffer.
 ///
@@ -93,6 +204,10 @@ use windows_sys::Win32::Foundation::UNICODE_STRING;
 ///   to the UTF-16 data.
 /// - `buffer`: A `Vec<u16>` that owns and manages the UTF-16 buffer, ensuring that its lifetime is tied to the `OwnedUnicodeString` structure.
 ///   The buffer's memory is automatically managed, reducing the risk of memory leaks or unsafe memory access.
+/// - `secret`: Whether the buffer is overwritten with zeros when the `OwnedUnicodeString` is dropped. See
+///   [`OwnedUnicodeString::zeroize_on_drop`].
+/// - `keep_terminator`: Whether a NUL terminator is kept in the buffer at all times, so that `MaximumLength`
+///   always leaves room for it. See [`OwnedUnicodeString::keep_null_terminator`].
 ///
 /// # Safety
 ///
@@ -103,6 +218,8 @@ use windows_sys::Win32::Foundation::UNICODE_STRING;
 pub struct OwnedUnicodeString {
     unicode_string: UNICODE_STRING,
     buffer: Vec<u16>,
+    secret: bool,
+    keep_terminator: bool,
 }
 
 impl OwnedUnicodeString {
@@ -112,49 +229,222 @@ impl OwnedUnicodeString {
 
     fn ensure_is_null_terminated(&mut self) {
         if !self.is_null_terminated() {
+            self.reserve_secret(1);
             self.buffer.push(0u16);
-            self.unicode_string.MaximumLength += size_of::<u16>() as u16;
+            self.refresh_buffer_pointer();
+            // The terminator may push the buffer one code unit past what `MaximumLength` can describe. That is
+            // harmless for a NUL-terminated pointer, so clamp rather than wrap.
+            let maximum_length = self.buffer.len() * size_of::<u16>();
+            self.unicode_string.MaximumLength = maximum_length.min(MAX_CODE_UNITS * size_of::<u16>()) as u16;
         }
     }
 
-    fn compute_size(&mut self) {
-        let maximum_length = (self.buffer.len() * size_of::<u16>()) as u16;
-        let mut count = 0;
+    fn refresh_buffer_pointer(&mut self) {
+        self.unicode_string.Buffer = self.buffer.as_mut_ptr();
+    }
 
-        if self.is_null_terminated() {
-            for &value in self.buffer.iter().rev() {
-                if value == 0 {
-                    count += 1;
-                } else {
-                    break;
-                }
+    fn compute_size(&mut self) -> Result<()> {
+        if self.keep_terminator && !self.is_null_terminated() {
+            self.reserve_secret(1);
+            self.buffer.push(0);
+        }
+        self.refresh_buffer_pointer();
+
+        let maximum_length = self.buffer.len() * size_of::<u16>();
+        let length = maximum_length - self.trailing_nul_count() * size_of::<u16>();
+
+        // The limit applies to the content, plus the terminator a `keep_null_terminator` string promises to fit.
+        // Any other trailing NUL, such as the one `ensure_is_null_terminated` adds to a full string, may lie beyond
+        // `MaximumLength`, which is capped instead.
+        let bytes = length + self.keep_terminator as usize * size_of::<u16>();
+        if bytes > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes });
+        }
+
+        self.unicode_string.Length = length as u16;
+        self.unicode_string.MaximumLength = maximum_length.min(MAX_CODE_UNITS * size_of::<u16>()) as u16;
+        debug_assert!(self.debug_check_invariants());
+        Ok(())
+    }
+
+    fn check_grow(&self, additional_code_units: usize) -> Result<()> {
+        let bytes = self
+            .len()
+            .saturating_add(additional_code_units)
+            .saturating_add(self.keep_terminator as usize)
+            .saturating_mul(size_of::<u16>());
+        if bytes > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes });
+        }
+        Ok(())
+    }
+
+    fn compute_size_or_panic(&mut self) {
+        if let Err(error) = self.compute_size() {
+            panic!("OwnedUnicodeString exceeds the UNICODE_STRING length limit: {:?}", error);
+        }
+    }
+
+    fn append<I>(&mut self, units: I)
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        if let Err(error) = self.try_append(units) {
+            panic!("OwnedUnicodeString exceeds the UNICODE_STRING length limit: {:?}", error);
+        }
+    }
+
+    fn try_append<I>(&mut self, units: I) -> Result<()>
+    where
+        I: IntoIterator<Item = u16>,
+    {
+        self.buffer.truncate(self.len());
+        if self.secret {
+            for unit in units {
+                self.reserve_secret(1);
+                self.buffer.push(unit);
             }
+        } else {
+            self.buffer.extend(units);
+        }
+        self.compute_size()
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u16] {
+        let length = self.len();
+        &mut self.buffer[..length]
+    }
+
+    /// Keeps only the code units in `[start, end)` of the logical content, preserving a NUL terminator.
+    fn retain_range(&mut self, start: usize, end: usize) {
+        let terminated = self.is_null_terminated();
+        self.buffer.truncate(end);
+        self.buffer.drain(..start);
+        if terminated {
+            self.buffer.push(0);
         }
+        self.compute_size_or_panic();
+    }
 
-        let length= maximum_length - (count * size_of::<u16>()) as u16;
+    fn wipe_if_secret(&mut self) {
+        if self.secret {
+            wipe_allocation(&mut self.buffer);
+        }
+    }
 
-        self.unicode_string.Length = length;
-        self.unicode_string.MaximumLength = maximum_length
+    /// Makes room for `additional` code units without letting `Vec` reallocate a secret buffer behind our back.
+    ///
+    /// A reallocation frees the old allocation without clearing it, so for secret strings the content is moved
+    /// into a fresh allocation by hand and the old one is wiped first. Non-secret strings are left to `Vec`.
+    fn reserve_secret(&mut self, additional: usize) {
+        if !self.secret || self.buffer.capacity() - self.buffer.len() >= additional {
+            return;
+        }
+        let capacity = (self.buffer.len() + additional).max(self.buffer.capacity() * 2);
+        self.move_secret_to(capacity);
     }
 
+    fn move_secret_to(&mut self, capacity: usize) {
+        let mut moved = Vec::with_capacity(capacity);
+        moved.extend_from_slice(&self.buffer);
+        let mut old = mem::replace(&mut self.buffer, moved);
+        wipe_allocation(&mut old);
+        self.refresh_buffer_pointer();
+    }
 
-}
+    #[cfg(feature = "unicode-case")]
+    fn map_chars<F>(&self, mut f: F) -> OwnedUnicodeString
+    where
+        F: FnMut(char) -> char,
+    {
+        let mut buffer = Vec::with_capacity(self.len());
+        for result in self.try_chars() {
+            match result {
+                Ok(c) => {
+                    let mut units = [0u16; 2];
+                    buffer.extend_from_slice(f(c).encode_utf16(&mut units));
+                }
+                Err(error) => buffer.push(error.unpaired_surrogate()),
+            }
+        }
+        Self::from(buffer)
+    }
 
-impl From<Vec<u16>> for OwnedUnicodeString {
-    /// Converts a `Vec<u16>` to an `OwnedUnicodeString`.
+    fn swap_prefix(&self, from: &str, to: &str) -> OwnedUnicodeString {
+        match self.strip_prefix(from) {
+            Some(mut rest) => {
+                rest.prepend(to);
+                rest
+            }
+            None => Self::from_wide(self.as_slice()),
+        }
+    }
+
+    fn check_no_embedded_nul(&self) -> Result<()> {
+        match self.as_slice().iter().position(|&unit| unit == 0) {
+            Some(index) => Err(UnicodeStringError::EmbeddedNul { index }),
+            None => Ok(()),
+        }
+    }
+
+    fn ends_with_by<F>(&self, suffix: &str, eq: F) -> bool
+    where
+        F: Fn(u16, u16) -> bool,
+    {
+        let slice = self.as_slice();
+        let suffix_len = suffix.encode_utf16().count();
+        if suffix_len > slice.len() {
+            return false;
+        }
+        slice[slice.len() - suffix_len..]
+            .iter()
+            .zip(suffix.encode_utf16())
+            .all(|(&a, b)| eq(a, b))
+    }
+
+    /// Returns `true` if the embedded `UNICODE_STRING` is consistent with the owned buffer.
     ///
-    /// This implementation takes ownership of the provided `Vec<u16>`, allowing for direct manipulation
-    /// of the UTF-16 buffer. It initializes an `UNICODE_STRING` with the provided vector, calculates
-    /// the length and maximum length of the buffer, and ensures that it remains valid and properly
-    /// managed throughout the instance's lifetime.
+    /// The following must hold at all times:
     ///
-    /// # Safety
+    /// - `Buffer` points to the first code unit of the owned vector, so it is never left dangling after a
+    ///   reallocation.
+    /// - `Length` is even, and `Length <= MaximumLength`.
+    /// - `MaximumLength` does not exceed the size of the owned buffer in bytes, so `Length / 2` code units are
+    ///   always readable.
     ///
-    /// The caller must ensure that the input `Vec<u16>` represents a valid UTF-16 encoded string.
-    /// This function will calculate the lengths based on the vector's contents and adjust the
-    /// `UNICODE_STRING` fields accordingly.
-    fn from(mut value: Vec<u16>) -> Self {
+    /// Every mutating method keeps these invariants, and debug builds assert them whenever the sizes are
+    /// recomputed. The check is cheap and is also meant for tests and for code that hands the `UNICODE_STRING`
+    /// to foreign APIs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("Hello");
+    /// my_string.reserve(100);
+    /// assert!(my_string.debug_check_invariants());
+    /// ```
+    pub fn debug_check_invariants(&self) -> bool {
+        let length = self.unicode_string.Length as usize;
+        let maximum_length = self.unicode_string.MaximumLength as usize;
+
+        core::ptr::eq(self.unicode_string.Buffer, self.buffer.as_ptr())
+            && length % size_of::<u16>() == 0
+            && length <= maximum_length
+            && maximum_length <= self.buffer.len() * size_of::<u16>()
+    }
 
+    /// Converts a `Vec<u16>` to an `OwnedUnicodeString`, returning an error instead of panicking.
+    ///
+    /// This is the fallible counterpart of `From<Vec<u16>>`. All lengths are computed in `usize` before being
+    /// stored in the `u16` fields of the `UNICODE_STRING`, so an oversized buffer is reported instead of
+    /// silently wrapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::TooLong`] if the buffer is longer than `u16::MAX` bytes.
+    pub fn try_from_vec(mut value: Vec<u16>) -> Result<OwnedUnicodeString> {
         let unicode_string = UNICODE_STRING {
             Length: 0,
             MaximumLength: 0,
@@ -164,291 +454,5638 @@ impl From<Vec<u16>> for OwnedUnicodeString {
         let mut result = Self {
             unicode_string,
             buffer: value,
+            secret: false,
+            keep_terminator: false,
         };
 
-        result.compute_size();
+        result.compute_size()?;
+
+        Ok(result)
+    }
 
+    /// Creates an `OwnedUnicodeString` holding sensitive data, such as a password, that is zeroed on drop.
+    ///
+    /// This is `OwnedUnicodeString::from(value)` followed by [`zeroize_on_drop`](Self::zeroize_on_drop). The
+    /// caller remains responsible for the `&str` it passed in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let password = OwnedUnicodeString::new_secret("hunter2");
+    /// assert!(password.is_secret());
+    /// ```
+    pub fn new_secret(value: &str) -> Self {
+        let mut result = Self::from(value);
+        result.zeroize_on_drop();
         result
+    }
+
+    /// Marks the string as secret, so that its buffer is overwritten with zeros before it is freed.
+    ///
+    /// The zeros are written with volatile stores followed by a compiler fence, which the compiler may not remove
+    /// even though the memory is about to be deallocated. The whole allocation is wiped, including spare capacity
+    /// that may still hold removed content. Only this instance is affected: strings derived from it, for example by
+    /// [`split_at`](Self::split_at) or [`replace`](Self::replace), are not secret, and a vector obtained through
+    /// [`into_vec`](Self::into_vec) becomes the caller's responsibility.
+    ///
+    /// # Performance
+    ///
+    /// Secret strings are more expensive than regular ones. Dropping one costs a volatile store per code unit of
+    /// capacity. Growing one never lets `Vec` reallocate, because that would free the old allocation uncleared:
+    /// the content is copied into a new allocation by hand and the old one is wiped, and appending pushes one code
+    /// unit at a time. [`shrink_to_fit`](Self::shrink_to_fit) likewise copies instead of shrinking in place.
+    pub fn zeroize_on_drop(&mut self) {
+        self.secret = true;
+    }
 
+    /// Returns `true` if the buffer is zeroed when the string is dropped.
+    pub fn is_secret(&self) -> bool {
+        self.secret
     }
-}
 
-impl From<&str> for OwnedUnicodeString {
-    /// Converts a Rust string slice (`&str`) to an `OwnedUnicodeString`.
+    /// Creates an `OwnedUnicodeString` that always keeps room for its NUL terminator.
     ///
-    /// This implementation encodes the Rust string as UTF-16 and stores the result in a `Vec<u16>`,
-    /// which is then used to initialize the `OwnedUnicodeString`. This allows for seamless integration
-    /// with Rust's native string types while leveraging the safety and efficiency of UTF-16 buffers.
+    /// This is `OwnedUnicodeString::from(value)` followed by
+    /// [`keep_null_terminator`](Self::keep_null_terminator).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use krnlstring::OwnedUnicodeString;
     ///
-    /// let my_string = OwnedUnicodeString::from("Hello, world!");
+    /// let my_string = OwnedUnicodeString::new_terminated("Hi");
+    /// assert_eq!(my_string.length(), 4);
+    /// assert_eq!(my_string.maximum_length(), 6);
     /// ```
-    fn from(value: &str) -> Self {
-        Self::from(value.encode_utf16().collect::<Vec<u16>>())
+    pub fn new_terminated(value: &str) -> Self {
+        let mut result = Self::from(value);
+        result.keep_null_terminator();
+        result
     }
-}
 
-impl AsRef<UNICODE_STRING> for OwnedUnicodeString {
-    /// Provides a reference to the internal `UNICODE_STRING`.
+    /// Keeps a NUL terminator in the buffer from now on, so that `MaximumLength` always includes it.
     ///
-    /// This implementation allows for safe access to the underlying `UNICODE_STRING` structure, which
-    /// can be useful for interoperability with Windows APIs that expect a `UNICODE_STRING` pointer.
-    /// The returned reference reflects the current state of the buffer and its lengths.
-    fn as_ref(&self) -> &UNICODE_STRING {
-        &self.unicode_string
+    /// Windows conventionally expects the `MaximumLength` of a buffer handed to an API to cover the content plus
+    /// a two-byte terminator. By default `MaximumLength` simply describes the buffer, which holds a terminator
+    /// only once one was added, for example by a `PCWSTR` conversion. In this mode a terminator is appended
+    /// immediately and restored after every modification, so `MaximumLength >= Length + 2` holds at all times and
+    /// converting to a `PCWSTR` never has to grow the buffer. [`trim_nul`](Self::trim_nul) consequently leaves
+    /// exactly one NUL behind.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the content together with its terminator is longer than a `UNICODE_STRING` can describe.
+    pub fn keep_null_terminator(&mut self) {
+        self.keep_terminator = true;
+        self.compute_size_or_panic();
     }
-}
 
-impl Into<PCWSTR> for &mut OwnedUnicodeString {
-    /// Converts a mutable reference to an `OwnedUnicodeString` into a `PCWSTR`.
+    /// Returns `true` if the string keeps room for its NUL terminator, see
+    /// [`keep_null_terminator`](Self::keep_null_terminator).
+    pub fn keeps_null_terminator(&self) -> bool {
+        self.keep_terminator
+    }
+
+    /// Creates a new, empty `OwnedUnicodeString`.
     ///
-    /// This conversion ensures that the UTF-16 buffer is null-terminated, as required for use
-    /// with many Windows API functions that expect a `PCWSTR` (a pointer to a constant, null-terminated
-    /// UTF-16 string). The conversion does not make a copy of the buffer, maintaining a zero-copy approach.
+    /// The buffer does not allocate until content is appended, and both `Length` and `MaximumLength` are zero.
+    /// The `Buffer` pointer is the empty vector's dangling-but-aligned pointer: it is valid to store and hand to
+    /// APIs together with a zero length, but it must never be dereferenced.
     ///
-    /// # Safety
+    /// # Examples
     ///
-    /// The buffer must remain valid for the lifetime of the `PCWSTR` returned. The caller should
-    /// ensure that the `OwnedUnicodeString` is not mutated in a way that invalidates the pointer.
-    fn into(self) -> PCWSTR {
-        self.ensure_is_null_terminated();
-        self.buffer.as_ptr()
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::new();
+    /// my_string.push_str("Hello, world!");
+    /// ```
+    pub fn new() -> Self {
+        Self::from(Vec::new())
     }
-}
 
-impl Into<PWSTR> for &mut OwnedUnicodeString{
-    /// Converts a mutable reference to an `OwnedUnicodeString` into a `PWSTR`.
+    /// Creates a new, empty `OwnedUnicodeString` with room for at least `code_units` code units.
     ///
-    /// Similar to `Into<PCWSTR>`, this conversion ensures that the UTF-16 buffer is properly null-terminated
-    /// and returns a mutable pointer (`PWSTR`). This is useful for APIs that require a mutable UTF-16 string buffer.
+    /// The string is empty, so `Length` and `MaximumLength` are both zero: `MaximumLength` describes the
+    /// initialized buffer, not its capacity. Appending up to `code_units` code units will not reallocate, so the
+    /// `Buffer` pointer stays stable in the meantime.
     ///
-    /// # Safety
+    /// # Examples
     ///
-    /// The buffer must remain valid and should not be modified in a way that would invalidate the pointer
-    /// while it is being used as a `PWSTR`.
-    fn into(self) -> PWSTR {
-        self.ensure_is_null_terminated();
-        self.buffer.as_mut_ptr()
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::with_capacity(32);
+    /// assert!(my_string.is_empty());
+    /// assert!(my_string.capacity_code_units() >= 32);
+    /// ```
+    pub fn with_capacity(code_units: usize) -> Self {
+        Self::from(Vec::with_capacity(code_units))
     }
-}
 
-impl fmt::Display for OwnedUnicodeString {
-    /// Formats the `OwnedUnicodeString` as a Rust string for display purposes.
+    /// Creates an `OwnedUnicodeString` by copying an existing UTF-16 slice.
     ///
-    /// This implementation provides a `Display` formatter that allows the `OwnedUnicodeString` to be printed
-    /// directly using Rust's `println!` and other formatting macros. It decodes the UTF-16 buffer to a Rust
-    /// string slice, converting any invalid UTF-16 sequences to the Unicode replacement character (`�`).
+    /// Unlike `From<Vec<u16>>`, this leaves the caller's buffer untouched. Only NUL code units at the very end of
+    /// the slice are treated as terminators and excluded from `Length`; NULs embedded before other content are
+    /// kept as part of the string.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use krnlstring::OwnedUnicodeString;
     ///
-    /// let my_string = OwnedUnicodeString::from("Hello, world!");
-    /// println!("{}", my_string);
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let utf16_slice = unsafe {
-            slice::from_raw_parts(
-                self.unicode_string.Buffer,
-                (self.unicode_string.Length / size_of::<u16>() as u16) as usize
-            )
-        };
-        for utf16 in decode_utf16(utf16_slice.iter().copied()) {
-            match utf16 {
-                Ok(ch) => write!(f, "{}", ch)?,
-                Err(_) => write!(f, "{}", "�")?,
+    /// let units = [0x0061, 0x0000, 0x0062, 0x0000];
+    /// let my_string = OwnedUnicodeString::from_wide(&units);
+    /// assert_eq!(my_string.as_slice(), &[0x0061, 0x0000, 0x0062]);
+    /// ```
+    pub fn from_wide(units: &[u16]) -> OwnedUnicodeString {
+        Self::from(units.to_vec())
+    }
+
+    /// Creates an `OwnedUnicodeString` from a Rust string slice, truncating it instead of failing if it is too long.
+    ///
+    /// At most `MAX_CODE_UNITS - 1` (32766) code units are kept, which leaves room for a NUL terminator within the
+    /// `MaximumLength` limit. Truncation happens at a character boundary, so a surrogate pair is never split and
+    /// the result may be one code unit shorter than the limit. This suits logging paths, where a truncated
+    /// string is preferable to an error; see [`try_from_vec`](Self::try_from_vec) and `From<&str>` for the
+    /// failing and panicking alternatives.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let message = "x".repeat(100_000);
+    /// let my_string = OwnedUnicodeString::from_str_truncated(&message);
+    /// assert_eq!(my_string.len(), 32766);
+    /// ```
+    pub fn from_str_truncated(s: &str) -> OwnedUnicodeString {
+        let limit = MAX_CODE_UNITS - 1;
+        let mut buffer = Vec::with_capacity(s.len().min(limit));
+        for c in s.chars() {
+            if buffer.len() + c.len_utf16() > limit {
+                break;
             }
+            let mut units = [0u16; 2];
+            buffer.extend_from_slice(c.encode_utf16(&mut units));
         }
-        Ok(())
+        Self::from(buffer)
     }
-}
-
-impl Add for OwnedUnicodeString {
-    type Output = OwnedUnicodeString;
 
-    /// Concatenates two `OwnedUnicodeString` instances.
+    /// Creates an `OwnedUnicodeString` from a Rust string slice without panicking or aborting.
     ///
-    /// This implementation of the `Add` trait allows for the concatenation of two `OwnedUnicodeString` instances,
-    /// resulting in a new `OwnedUnicodeString` that contains the combined UTF-16 buffers of the operands.
-    /// It ensures that the resulting buffer is properly null-terminated and that the lengths are updated accordingly.
+    /// This is the allocation-safe counterpart of `From<&str>` for drivers and other contexts where running out of
+    /// memory must be handled. The UTF-16 length is computed first and checked against [`MAX_BYTES`], and the
+    /// buffer is then allocated in one step with [`try_reserve`](Self::try_reserve), so the encoding itself never
+    /// reallocates. If the allocator fails, the error is returned instead of invoking the global allocation error
+    /// handler, which would abort the process or bug-check the system.
     ///
-    /// # Safety
+    /// # Errors
     ///
-    /// The internal buffer is resized to accommodate the concatenated strings, and lengths are recalculated to prevent
-    /// overflows or invalid reads.
+    /// Returns [`UnicodeStringError::TooLong`] if the encoded string is longer than `u16::MAX` bytes, or
+    /// [`UnicodeStringError::AllocFailed`] if the buffer could not be allocated.
     ///
-    fn add(mut self, rhs: Self) -> Self::Output {
-        let rhs_slice = unsafe {
-            slice::from_raw_parts(
-                rhs.unicode_string.Buffer,
-                (rhs.unicode_string.Length / size_of::<u16>() as u16) as usize
-            )
-        };
-        self.buffer.extend(rhs_slice);
-        self.compute_size();
-        self
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::try_from_str("\\Device\\Null").unwrap();
+    /// assert!(my_string == "\\Device\\Null");
+    /// assert!(OwnedUnicodeString::try_from_str(&"x".repeat(40000)).is_err());
+    /// ```
+    pub fn try_from_str(s: &str) -> Result<OwnedUnicodeString> {
+        let code_units = s.encode_utf16().count();
+        let bytes = code_units * size_of::<u16>();
+        if bytes > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes });
+        }
+        let mut owned = Self::new();
+        owned.try_reserve(code_units)?;
+        owned.buffer.extend(s.encode_utf16());
+        owned.compute_size()?;
+        Ok(owned)
     }
-}
 
-impl Add<&str> for OwnedUnicodeString {
-    type Output = OwnedUnicodeString;
-
-    /// Concatenates an `OwnedUnicodeString` with a Rust string slice (`&str`).
+    /// Creates an `OwnedUnicodeString` from UTF-8 encoded bytes, such as data read from a file.
     ///
-    /// This implementation allows for concatenating a Rust `&str` directly onto an `OwnedUnicodeString`, returning a new
-    /// `OwnedUnicodeString` with the combined content. The string slice is encoded as UTF-16 before concatenation.
-    fn add(self, rhs: &str) -> Self::Output {
-        let other = OwnedUnicodeString::from(rhs);
-        self + other
+    /// The bytes are validated with `core::str::from_utf8` and then encoded to UTF-16. See
+    /// [`from_utf8_lossy`](Self::from_utf8_lossy) to accept malformed input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::InvalidUtf8`] with the offset of the first invalid byte sequence, or
+    /// [`UnicodeStringError::TooLong`] if the encoded string is longer than `u16::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::{OwnedUnicodeString, UnicodeStringError};
+    ///
+    /// assert!(OwnedUnicodeString::from_utf8("Grüße".as_bytes()).unwrap() == OwnedUnicodeString::from("Grüße"));
+    /// assert_eq!(
+    ///     OwnedUnicodeString::from_utf8(b"ab\xFF").err(),
+    ///     Some(UnicodeStringError::InvalidUtf8 { index: 2 })
+    /// );
+    /// ```
+    pub fn from_utf8(bytes: &[u8]) -> Result<OwnedUnicodeString> {
+        match core::str::from_utf8(bytes) {
+            Ok(s) => Self::try_from_vec(s.encode_utf16().collect()),
+            Err(error) => Err(UnicodeStringError::InvalidUtf8 { index: error.valid_up_to() }),
+        }
     }
-}
-
 
-impl PartialEq for OwnedUnicodeString {
+    /// Creates an `OwnedUnicodeString` from UTF-8 encoded bytes, replacing invalid sequences with `U+FFFD`.
+    ///
+    /// Each maximal invalid byte sequence becomes one replacement character, exactly as with
+    /// `String::from_utf8_lossy`. The bytes are encoded straight to UTF-16 without an intermediate `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoded string is longer than a `UNICODE_STRING` can describe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from_utf8_lossy(b"ab\xFFc");
+    /// assert!(my_string == OwnedUnicodeString::from("ab\u{FFFD}c"));
+    /// ```
+    pub fn from_utf8_lossy(bytes: &[u8]) -> OwnedUnicodeString {
+        let mut buffer = Vec::with_capacity(bytes.len());
+        for chunk in bytes.utf8_chunks() {
+            buffer.extend(chunk.valid().encode_utf16());
+            if !chunk.invalid().is_empty() {
+                buffer.push(char::REPLACEMENT_CHARACTER as u16);
+            }
+        }
+        Self::from(buffer)
+    }
 
-    /// Compares two `OwnedUnicodeString` instances for equality.
+    /// Creates an `OwnedUnicodeString` from single-byte text, interpreting each byte as a Latin-1 character.
     ///
-    /// This implementation of the `PartialEq` trait allows for the comparison of two `OwnedUnicodeString` instances
-    /// based on the contents of their UTF-16 buffers. It checks if the lengths and contents of both buffers match,
-    /// providing a simple and efficient way to compare Unicode strings.
-    fn eq(&self, other: &Self) -> bool {
-        let self_slice = &self.buffer[..(self.unicode_string.Length / size_of::<u16>() as u16) as usize];
-        let other_slice = &other.buffer[..(other.unicode_string.Length / size_of::<u16>() as u16) as usize];
-        self_slice == other_slice
+    /// Every byte is widened to the code unit of the same value, which is exact for ASCII and for ISO 8859-1
+    /// (Latin-1), since their characters occupy the first 256 Unicode code points. No code page is consulted, so
+    /// bytes from other ANSI code pages such as Windows-1252 or Shift JIS are not translated: `0x80`, for instance,
+    /// becomes U+0080 rather than `€`. Unlike `From<&OwnedAnsiString>`, bytes above `0x7F` are kept instead of
+    /// being replaced with `?`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is longer than [`MAX_CODE_UNITS`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from_ansi(b"caf\xE9");
+    /// assert!(my_string == "café");
+    /// ```
+    pub fn from_ansi(bytes: &[u8]) -> OwnedUnicodeString {
+        Self::from(bytes.iter().map(|&byte| byte as u16).collect::<Vec<u16>>())
     }
-}
 
-#[cfg(test)]
-mod test_krnlstring {
-    use alloc::{format, vec};
-    use super::*;
+    /// Creates an `OwnedUnicodeString` from a fixed-size buffer whose content ends at the first NUL.
+    ///
+    /// This is the usual layout of C-style `WCHAR name[N]` fields: only the code units before the first NUL are
+    /// copied, and whatever follows it is ignored. If the buffer contains no NUL, the whole slice is copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let field: [u16; 8] = [0x0043, 0x003A, 0x0000, 0x0058, 0x0058, 0x0058, 0x0058, 0x0058];
+    /// let my_string = OwnedUnicodeString::from_slice_until_nul(&field);
+    /// assert!(my_string == OwnedUnicodeString::from("C:"));
+    /// ```
+    pub fn from_slice_until_nul(buf: &[u16]) -> OwnedUnicodeString {
+        let end = buf.iter().position(|&unit| unit == 0).unwrap_or(buf.len());
+        Self::from_wide(&buf[..end])
+    }
+
+    /// Creates an `OwnedUnicodeString` by copying a NUL-terminated wide string.
+    ///
+    /// The string is scanned up to its first NUL code unit, and the code units before it are copied into a new
+    /// owned buffer. To avoid a runaway read on a malformed pointer, the scan stops after the largest number of
+    /// code units a `UNICODE_STRING` can describe (32767), and the copy is truncated there. A null pointer
+    /// produces an empty string.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must either be null or point to a readable sequence of `u16` values that is terminated by a NUL
+    /// or at least 32767 code units long.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let wide = [0x0048u16, 0x0069, 0x0000];
+    /// let my_string = unsafe { OwnedUnicodeString::from_pcwstr(wide.as_ptr()) };
+    /// assert!(my_string == OwnedUnicodeString::from("Hi"));
+    /// ```
+    pub unsafe fn from_pcwstr(ptr: PCWSTR) -> OwnedUnicodeString {
+        if ptr.is_null() {
+            return Self::new();
+        }
+        let mut length = 0;
+        while length < MAX_CODE_UNITS && unsafe { *ptr.add(length) } != 0 {
+            length += 1;
+        }
+        Self::from_wide(unsafe { slice::from_raw_parts(ptr, length) })
+    }
+
+    /// Creates an `OwnedUnicodeString` by copying `len` code units from a raw pointer.
+    ///
+    /// This is intended for callbacks that hand out a pointer and an explicit length rather than a NUL-terminated
+    /// string. The `len` code units are copied into a new owned buffer as with [`from_wide`](Self::from_wide),
+    /// so trailing NULs are treated as terminators and embedded ones are kept. When `len` is zero the pointer is
+    /// not read and an empty string is returned, so a null pointer is accepted in that case.
+    ///
+    /// # Safety
+    ///
+    /// Unless `len` is zero, `ptr` must be non-null, properly aligned and valid for reads of `len` consecutive
+    /// `u16` values, and the memory must not be mutated for the duration of the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` code units do not fit into a `UNICODE_STRING`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let wide: Vec<u16> = "Hello".encode_utf16().collect();
+    /// let my_string = unsafe { OwnedUnicodeString::from_wide_ptr_len(wide.as_ptr(), 4) };
+    /// assert!(my_string == "Hell");
+    /// ```
+    pub unsafe fn from_wide_ptr_len(ptr: *const u16, len: usize) -> OwnedUnicodeString {
+        if len == 0 {
+            return Self::new();
+        }
+        Self::from_wide(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Builds a `REG_MULTI_SZ` buffer from a list of strings.
+    ///
+    /// The logical content of each string is written followed by a single NUL, and a final NUL terminates the
+    /// list, producing the layout expected by `RegSetValueEx` with `REG_MULTI_SZ`. An empty list produces just a
+    /// double NUL. Since an empty entry would read as the end of the list, empty strings cannot be represented
+    /// in the middle of a `REG_MULTI_SZ` value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let values = [OwnedUnicodeString::from("a"), OwnedUnicodeString::from("b")];
+    /// assert_eq!(OwnedUnicodeString::to_multi_sz(&values), vec![0x0061, 0, 0x0062, 0, 0]);
+    /// ```
+    pub fn to_multi_sz(strings: &[OwnedUnicodeString]) -> Vec<u16> {
+        let total = strings.iter().map(|string| string.len() + 1).sum::<usize>();
+        let mut buffer = Vec::with_capacity(total.max(1) + 1);
+        for string in strings {
+            buffer.extend_from_slice(string.as_slice());
+            buffer.push(0);
+        }
+        if strings.is_empty() {
+            buffer.push(0);
+        }
+        buffer.push(0);
+        buffer
+    }
+
+    /// Parses a `REG_MULTI_SZ` buffer into a list of strings.
+    ///
+    /// The buffer is split on single NULs and parsing stops at the first empty entry, that is at the
+    /// terminating double NUL. An empty buffer or a buffer holding only a double NUL yields an empty list, and a
+    /// buffer missing its final NUL still yields its last entry.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let values = OwnedUnicodeString::from_multi_sz(&[0x0061, 0, 0x0062, 0, 0]);
+    /// assert_eq!(values.len(), 2);
+    /// ```
+    pub fn from_multi_sz(buf: &[u16]) -> Vec<OwnedUnicodeString> {
+        buf.split(|&unit| unit == 0)
+            .take_while(|entry| !entry.is_empty())
+            .map(Self::from_wide)
+            .collect()
+    }
+
+    /// Parses a Windows environment block into `(name, value)` pairs.
+    ///
+    /// An environment block has the form `Name=Value\0Name=Value\0\0`. Entries are split on single NULs exactly
+    /// like [`from_multi_sz`](Self::from_multi_sz), stopping at the terminating double NUL. Each entry is then
+    /// split at its first `=`:
+    ///
+    /// - The search for `=` starts at the second code unit, so the hidden per-drive variables Windows keeps,
+    ///   such as `=C:=C:\Windows`, parse as the name `=C:` and the value `C:\Windows`.
+    /// - An entry without any `=` is kept as a name with an empty value rather than dropped.
+    /// - The value may itself contain `=`; only the first separator splits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let block: Vec<u16> = "=C:=C:\\\0PATH=C:\\Windows\0\0".encode_utf16().collect();
+    /// let variables = OwnedUnicodeString::parse_env_block(&block);
+    /// assert_eq!(variables.len(), 2);
+    /// assert!(variables[0].0 == OwnedUnicodeString::from("=C:"));
+    /// assert!(variables[1].1 == OwnedUnicodeString::from("C:\\Windows"));
+    /// ```
+    pub fn parse_env_block(buf: &[u16]) -> Vec<(OwnedUnicodeString, OwnedUnicodeString)> {
+        const EQUALS: u16 = b'=' as u16;
+
+        buf.split(|&unit| unit == 0)
+            .take_while(|entry| !entry.is_empty())
+            .map(|entry| match entry[1..].iter().position(|&unit| unit == EQUALS) {
+                Some(position) => {
+                    let separator = position + 1;
+                    (Self::from_wide(&entry[..separator]), Self::from_wide(&entry[separator + 1..]))
+                }
+                None => (Self::from_wide(entry), Self::new()),
+            })
+            .collect()
+    }
+
+    /// Builds a registry or NT object path by joining `segments` with `\`.
+    ///
+    /// Exactly one separator ends up between consecutive components: separators at the start and end of each
+    /// segment are trimmed, runs of separators inside a segment are collapsed, and segments that are empty after
+    /// trimming are skipped. The path is absolute, starting with `\`, if the first non-empty segment starts
+    /// with a separator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path is longer than a `UNICODE_STRING` can describe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::registry_path(&["\\Registry\\", "\\Machine", "SYSTEM\\\\CurrentControlSet"]);
+    /// assert!(path == OwnedUnicodeString::from("\\Registry\\Machine\\SYSTEM\\CurrentControlSet"));
+    /// ```
+    pub fn registry_path(segments: &[&str]) -> OwnedUnicodeString {
+        const SEPARATOR: u16 = b'\\' as u16;
+
+        let absolute = segments
+            .iter()
+            .find(|segment| !segment.is_empty())
+            .is_some_and(|segment| segment.starts_with('\\'));
+        let mut buffer = Vec::new();
+        let components = segments
+            .iter()
+            .flat_map(|segment| segment.split('\\'))
+            .filter(|component| !component.is_empty());
+
+        for component in components {
+            if absolute || !buffer.is_empty() {
+                buffer.push(SEPARATOR);
+            }
+            buffer.extend(component.encode_utf16());
+        }
+        if absolute && buffer.is_empty() {
+            buffer.push(SEPARATOR);
+        }
+
+        Self::from(buffer)
+    }
+
+    /// Formats a GUID in its canonical registry form, `{XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}`.
+    ///
+    /// The hexadecimal digits are uppercase, matching the way GUIDs appear in registry keys such as device
+    /// interface classes. The 38 code units are written straight into the UTF-16 buffer without an intermediate
+    /// `String`, so the result can be appended to a registry path directly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    /// use windows_sys::core::GUID;
+    ///
+    /// let guid = GUID::from_u128(0x4d36e968_e325_11ce_bfc1_08002be10318);
+    /// let my_string = OwnedUnicodeString::from_guid(&guid);
+    /// assert!(my_string == "{4D36E968-E325-11CE-BFC1-08002BE10318}");
+    /// ```
+    pub fn from_guid(guid: &GUID) -> OwnedUnicodeString {
+        fn push_hex(buffer: &mut Vec<u16>, value: u64, digits: u32) {
+            for shift in (0..digits).rev() {
+                let nibble = (value >> (shift * 4)) & 0xF;
+                buffer.push(b"0123456789ABCDEF"[nibble as usize] as u16);
+            }
+        }
+
+        let mut buffer = Vec::with_capacity(38);
+        buffer.push(b'{' as u16);
+        push_hex(&mut buffer, guid.data1 as u64, 8);
+        buffer.push(b'-' as u16);
+        push_hex(&mut buffer, guid.data2 as u64, 4);
+        buffer.push(b'-' as u16);
+        push_hex(&mut buffer, guid.data3 as u64, 4);
+        buffer.push(b'-' as u16);
+        push_hex(&mut buffer, u16::from_be_bytes([guid.data4[0], guid.data4[1]]) as u64, 4);
+        buffer.push(b'-' as u16);
+        for &byte in &guid.data4[2..] {
+            push_hex(&mut buffer, byte as u64, 2);
+        }
+        buffer.push(b'}' as u16);
+        Self::from(buffer)
+    }
+
+    /// Creates an `OwnedUnicodeString` by copying `slice`, reserving `extra_code_units` of additional capacity.
+    ///
+    /// Reserving the tail space up front means that appending up to `extra_code_units` code units later will not
+    /// reallocate the buffer, so the `Buffer` pointer of the internal `UNICODE_STRING` stays stable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let device: Vec<u16> = "\\Device\\".encode_utf16().collect();
+    /// let mut path = OwnedUnicodeString::from_wide_with_capacity(&device, 16);
+    /// path.push_str("HarddiskVolume1");
+    /// ```
+    pub fn from_wide_with_capacity(slice: &[u16], extra_code_units: usize) -> Self {
+        let mut buffer = Vec::with_capacity(slice.len() + extra_code_units);
+        buffer.extend_from_slice(slice);
+        Self::from(buffer)
+    }
+
+    /// Returns the logical UTF-16 content of the string.
+    ///
+    /// The returned slice covers `Length` bytes of the buffer and therefore excludes any trailing
+    /// NUL terminators.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.buffer[..self.unicode_string.Length as usize / size_of::<u16>()]
+    }
+
+    /// Returns the code units in `range`, or `None` if the range is not within the logical content.
+    ///
+    /// Unlike indexing, this never panics, which makes it suitable for parsing untrusted input. The range is
+    /// checked against [`len`](Self::len), so the trailing NUL terminator is never part of the result. A range
+    /// whose start is greater than its end also yields `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("C:\\Windows");
+    /// assert_eq!(my_string.get(0..2), Some(&[0x0043, 0x003A][..]));
+    /// assert_eq!(my_string.get(8..20), None);
+    /// ```
+    pub fn get(&self, range: Range<usize>) -> Option<&[u16]> {
+        self.as_slice().get(range)
+    }
+
+    /// Returns the code units in `range` mutably, or `None` if the range is not within the logical content.
+    ///
+    /// The length of the string is not changed by editing the returned slice. Writing a NUL code unit into it
+    /// creates an embedded NUL, which `PCWSTR` consumers will treat as the end of the string.
+    pub fn get_mut(&mut self, range: Range<usize>) -> Option<&mut [u16]> {
+        self.as_mut_slice().get_mut(range)
+    }
+
+    /// Returns the logical content of the string followed by its NUL terminator.
+    ///
+    /// If the buffer is not already NUL-terminated, a terminator is appended first and the `Buffer` pointer is
+    /// refreshed, which is why this method takes `&mut self`. The returned slice covers `Length / 2 + 1` code
+    /// units.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("Hi");
+    /// assert_eq!(my_string.as_null_terminated_slice(), &[0x0048, 0x0069, 0x0000]);
+    /// ```
+    pub fn as_null_terminated_slice(&mut self) -> &[u16] {
+        self.ensure_is_null_terminated();
+        &self.buffer[..self.len() + 1]
+    }
+
+    /// Removes all trailing NUL code units from the buffer, in place.
+    ///
+    /// This is the inverse of the NUL termination performed by [`as_null_terminated_slice`] and the `PCWSTR`
+    /// conversions, and is useful for normalizing buffers returned by APIs that pad with several NULs. `Length`
+    /// already excludes trailing NULs and is unchanged; `MaximumLength` shrinks to match it. The capacity is kept,
+    /// so the `Buffer` pointer stays the same.
+    ///
+    /// [`as_null_terminated_slice`]: Self::as_null_terminated_slice
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from_wide(&[0x0041, 0x0000, 0x0000]);
+    /// my_string.trim_nul();
+    /// assert_eq!(my_string.maximum_length(), my_string.length());
+    /// ```
+    pub fn trim_nul(&mut self) {
+        self.buffer.truncate(self.len());
+        self.compute_size_or_panic();
+    }
+
+    /// Returns the number of NUL code units at the end of the buffer.
+    ///
+    /// These are the terminators that `Length` excludes, so this is the difference between
+    /// [`maximum_length`](Self::maximum_length) and [`length`](Self::length) in code units. It is zero after
+    /// [`trim_nul`](Self::trim_nul) and usually one after adding a terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from(vec![0x0041, 0x0000, 0x0000]);
+    /// assert_eq!(my_string.trailing_nul_count(), 2);
+    /// ```
+    pub fn trailing_nul_count(&self) -> usize {
+        self.buffer.iter().rev().take_while(|&&value| value == 0).count()
+    }
+
+    /// Returns the logical content of the string as raw bytes.
+    ///
+    /// The slice is `Length` bytes long and reinterprets the UTF-16 code units in native byte order, which is
+    /// little-endian on Windows targets. This is useful for writing the raw content to a file or hashing it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("AB");
+    /// assert_eq!(my_string.as_bytes().len(), 4);
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        let content = self.as_slice();
+        // SAFETY: `content` is a valid, initialized `[u16]`; any `u16` is two valid bytes and `u8` has an
+        // alignment of one, so the same memory can be viewed as `content.len() * 2` bytes.
+        unsafe { slice::from_raw_parts(content.as_ptr().cast::<u8>(), size_of_val(content)) }
+    }
+
+    /// Formats every code unit of the buffer as `0xXXXX`, separated by spaces, for diagnosing encoding issues.
+    ///
+    /// The whole buffer is dumped, including trailing NUL terminators that [`as_slice`](Self::as_slice) hides,
+    /// and invalid UTF-16 is shown as is rather than replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("Hi");
+    /// my_string.as_null_terminated_slice();
+    /// assert_eq!(my_string.hex_dump(), "0x0048 0x0069 0x0000");
+    /// ```
+    pub fn hex_dump(&self) -> String {
+        let mut dump = String::with_capacity(self.buffer.len() * 7);
+        for (index, unit) in self.buffer.iter().enumerate() {
+            if index > 0 {
+                dump.push(' ');
+            }
+            // Writing to a `String` cannot fail.
+            let _ = write!(dump, "{:#06X}", unit);
+        }
+        dump
+    }
+
+    /// Converts the logical content to single-byte characters, replacing every non-ASCII character with `?`.
+    ///
+    /// This is a conservative, dependency-free approximation of the conversion performed for an ANSI `STRING`:
+    /// ASCII characters map to themselves, while any other character, including a surrogate pair and an
+    /// unpaired surrogate, becomes a single `?` byte. No code page is consulted, so the result is the same on
+    /// every system. It is intended for logging and debug output; no trailing NUL byte is added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("Grüße");
+    /// assert_eq!(my_string.to_ansi_lossy(), b"Gr??e");
+    /// ```
+    pub fn to_ansi_lossy(&self) -> Vec<u8> {
+        self.chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+            .collect()
+    }
+
+    /// Returns `true` if every code unit of the logical content is ASCII, that is below `0x80`.
+    ///
+    /// Device and driver names are usually pure ASCII, and this check lets callers take a cheaper processing
+    /// path for them. An empty string is ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::from("\\Device\\Null").is_ascii());
+    /// assert!(!OwnedUnicodeString::from("Grüße").is_ascii());
+    /// ```
+    pub fn is_ascii(&self) -> bool {
+        self.code_units().all(|unit| unit < 0x80)
+    }
+
+    /// Converts the string to a Rust `String` if it is pure ASCII, or returns `None` otherwise.
+    ///
+    /// A UTF-16 buffer can never be borrowed as a `&str`, but for ASCII content every code unit maps to exactly
+    /// one byte, so the conversion is guaranteed to be lossless. Unlike `Display` or
+    /// [`to_ansi_lossy`](Self::to_ansi_lossy), no replacement character is ever substituted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert_eq!(OwnedUnicodeString::from("Tcpip").to_ascii_string().as_deref(), Some("Tcpip"));
+    /// assert_eq!(OwnedUnicodeString::from("Grüße").to_ascii_string(), None);
+    /// ```
+    pub fn to_ascii_string(&self) -> Option<String> {
+        if !self.is_ascii() {
+            return None;
+        }
+        Some(self.code_units().map(|unit| unit as u8 as char).collect())
+    }
+
+    /// Consumes the `OwnedUnicodeString` and returns its buffer, without the trailing NUL terminator.
+    ///
+    /// The buffer is handed over without copying. Taking `self` by value guarantees that no `UNICODE_STRING`
+    /// pointing into the returned vector survives. See [`into_vec_with_nul`](Self::into_vec_with_nul) to keep
+    /// the terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let units = OwnedUnicodeString::from("AB").into_vec();
+    /// assert_eq!(units, vec![0x0041, 0x0042]);
+    /// ```
+    pub fn into_vec(mut self) -> Vec<u16> {
+        let length = self.len();
+        let mut buffer = mem::take(&mut self.buffer);
+        buffer.truncate(length);
+        buffer
+    }
+
+    /// Consumes the `OwnedUnicodeString` and returns its buffer, terminated by exactly one NUL code unit.
+    pub fn into_vec_with_nul(self) -> Vec<u16> {
+        let mut buffer = self.into_vec();
+        buffer.push(0);
+        buffer
+    }
+
+    /// Consumes the `OwnedUnicodeString` and returns a `UNICODE_STRING` that owns the leaked buffer.
+    ///
+    /// This mirrors `Box::into_raw` and is meant for handing ownership across an FFI boundary. The buffer is
+    /// shrunk to exactly `MaximumLength` bytes and then leaked, so `Buffer` points to an allocation that is no
+    /// longer managed by Rust. To release it, pass the value back to [`from_raw`](Self::from_raw); otherwise the
+    /// memory leaks. For secret strings the spare capacity is wiped before it is released, but the leaked buffer
+    /// itself is not wiped until it is reclaimed and dropped.
+    ///
+    /// A terminator that lies beyond `MaximumLength`, as left by a `PCWSTR` conversion of a string of
+    /// [`MAX_CODE_UNITS`] code units, is dropped first, so that the leaked allocation is always exactly the one
+    /// `from_raw` rebuilds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let raw = OwnedUnicodeString::from("Hi").into_raw();
+    /// assert_eq!(raw.Length, 4);
+    /// let my_string = unsafe { OwnedUnicodeString::from_raw(raw) };
+    /// assert!(my_string == "Hi");
+    /// ```
+    pub fn into_raw(mut self) -> UNICODE_STRING {
+        self.buffer.truncate(self.unicode_string.MaximumLength as usize / size_of::<u16>());
+        if self.secret && self.buffer.capacity() != self.buffer.len() {
+            self.move_secret_to(self.buffer.len());
+        }
+        let mut raw = self.unicode_string;
+        let buffer = mem::take(&mut self.buffer).into_boxed_slice();
+        raw.Buffer = Box::into_raw(buffer) as *mut u16;
+        raw
+    }
+
+    /// Reclaims ownership of a buffer previously leaked by [`into_raw`](Self::into_raw).
+    ///
+    /// The buffer is rebuilt from `Buffer` and `MaximumLength` without copying, and `Length` is recomputed from
+    /// its content. The secret and terminator-keeping modes are not carried through the raw form; re-enable them
+    /// on the result if needed.
+    ///
+    /// # Safety
+    ///
+    /// `us` must have been produced by [`into_raw`](Self::into_raw), with `Buffer` and `MaximumLength` left
+    /// unchanged, and must not be reclaimed more than once. Passing a `UNICODE_STRING` from any other source,
+    /// such as one filled in by the system, is undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let raw = OwnedUnicodeString::from("\\Device\\Null").into_raw();
+    /// let my_string = unsafe { OwnedUnicodeString::from_raw(raw) };
+    /// assert_eq!(my_string.to_string(), "\\Device\\Null");
+    /// ```
+    pub unsafe fn from_raw(us: UNICODE_STRING) -> OwnedUnicodeString {
+        let code_units = us.MaximumLength as usize / size_of::<u16>();
+        let buffer = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(us.Buffer, code_units)) };
+        Self::from(buffer.into_vec())
+    }
+
+    /// Returns the length of the string in UTF-16 code units.
+    ///
+    /// This is `Length / 2` and excludes any trailing NUL terminator. Characters outside the Basic Multilingual
+    /// Plane are encoded as surrogate pairs and count twice; use [`chars_count`](Self::chars_count) for the
+    /// number of characters.
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns the number of Unicode scalar values in the string.
+    ///
+    /// **This is not the same as [`len`](Self::len)**, which counts UTF-16 code units: a surrogate pair counts
+    /// as two code units but as a single character here. Each invalid sequence counts as one replacement
+    /// character. Computing the count requires decoding the whole string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("𐐷");
+    /// assert_eq!(my_string.len(), 2);
+    /// assert_eq!(my_string.chars_count(), 1);
+    /// ```
+    pub fn chars_count(&self) -> usize {
+        self.try_chars().count()
+    }
+
+    /// Returns `true` if the string has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the `Length` field of the internal `UNICODE_STRING`, in bytes.
+    ///
+    /// This is the size of the logical content, excluding trailing NUL terminators, and always equals
+    /// `len() * 2`.
+    pub fn length(&self) -> u16 {
+        self.unicode_string.Length
+    }
+
+    /// Returns the `MaximumLength` field of the internal `UNICODE_STRING`, in bytes.
+    ///
+    /// This is the size of the initialized buffer, including any trailing NUL terminators. It does not include
+    /// spare capacity; see [`capacity_code_units`](Self::capacity_code_units) for that.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("Hi");
+    /// my_string.as_null_terminated_slice();
+    /// assert_eq!(my_string.length(), 4);
+    /// assert_eq!(my_string.maximum_length(), 6);
+    /// ```
+    pub fn maximum_length(&self) -> u16 {
+        self.unicode_string.MaximumLength
+    }
+
+    /// Returns the number of code units the underlying buffer can hold without reallocating.
+    ///
+    /// The capacity can exceed what `MaximumLength` is able to describe; only the initialized part of the
+    /// buffer is ever reported to Windows.
+    pub fn capacity_code_units(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Returns a bitwise copy of the internal `UNICODE_STRING`, for APIs that take it by value.
+    ///
+    /// The copy has the same `Buffer` pointer and the same lengths as [`as_ref`](AsRef::as_ref) would show.
+    ///
+    /// # Lifetime hazard
+    ///
+    /// Although the returned struct is a plain value, it still points into `self`'s buffer. It must not be used
+    /// after `self` is dropped, nor after any call that may reallocate the buffer (for example
+    /// [`push_str`](Self::push_str) or [`reserve`](Self::reserve)), since its `Buffer` would then dangle. The
+    /// compiler cannot check this.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    /// use windows_sys::Win32::Foundation::UNICODE_STRING;
+    ///
+    /// fn first_unit(value: UNICODE_STRING) -> u16 {
+    ///     unsafe { *value.Buffer }
+    /// }
+    ///
+    /// let my_string = OwnedUnicodeString::from("Hi");
+    /// assert_eq!(first_unit(my_string.to_unicode_string()), 0x0048);
+    /// ```
+    pub fn to_unicode_string(&self) -> UNICODE_STRING {
+        self.unicode_string
+    }
+
+    /// Returns a mutable reference to the internal `UNICODE_STRING`, for APIs that fill in an OUT parameter.
+    ///
+    /// The API can write up to `MaximumLength` bytes through `Buffer`, so size the buffer first, for example with
+    /// `OwnedUnicodeString::from(vec![0u16; 260])`, which yields an empty string with a `MaximumLength` of 520
+    /// bytes. The required call sequence is:
+    ///
+    /// 1. Call `as_mut_unicode_string` and pass the result to the API.
+    /// 2. Call [`resync_from_length`](Self::resync_from_length) before using the string in any other way.
+    ///
+    /// Until step 2, the `Length` written by the API is not reflected in the owned buffer, and methods other
+    /// than `resync_from_length` may observe inconsistent state. The API must not change `Buffer` or increase
+    /// `MaximumLength`.
+    pub fn as_mut_unicode_string(&mut self) -> &mut UNICODE_STRING {
+        &mut self.unicode_string
+    }
+
+    /// Brings the owned buffer back in line with a `Length` written through
+    /// [`as_mut_unicode_string`](Self::as_mut_unicode_string).
+    ///
+    /// The API-updated `Length` is trusted: the buffer is truncated to `Length / 2` code units and the sizes are
+    /// recomputed, so `MaximumLength` shrinks to the written content. The allocation is kept, so the `Buffer`
+    /// pointer does not change. As everywhere in this crate, NULs at the end of the written content are treated
+    /// as terminators and excluded from `Length`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Buffer` no longer points to the owned buffer, if `Length` is greater than `MaximumLength`, or
+    /// if `MaximumLength` exceeds the size of the owned buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from(vec![0u16; 16]);
+    /// let raw = my_string.as_mut_unicode_string();
+    /// unsafe { raw.Buffer.write(0x0041) };
+    /// raw.Length = 2;
+    ///
+    /// my_string.resync_from_length();
+    /// assert!(my_string == OwnedUnicodeString::from("A"));
+    /// ```
+    pub fn resync_from_length(&mut self) {
+        let length = self.unicode_string.Length as usize;
+        let maximum_length = self.unicode_string.MaximumLength as usize;
+
+        assert!(
+            core::ptr::eq(self.unicode_string.Buffer, self.buffer.as_ptr()),
+            "UNICODE_STRING Buffer was changed and no longer points to the owned buffer"
+        );
+        assert!(length <= maximum_length, "UNICODE_STRING Length exceeds MaximumLength");
+        assert!(
+            maximum_length <= self.buffer.len() * size_of::<u16>(),
+            "UNICODE_STRING MaximumLength exceeds the owned buffer"
+        );
+
+        self.buffer.truncate(length / size_of::<u16>());
+        self.compute_size_or_panic();
+    }
+
+    /// Returns an iterator over the raw UTF-16 code units of the string, excluding any trailing NUL terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("AB");
+    /// let checksum = my_string.code_units().fold(0u32, |sum, unit| sum + unit as u32);
+    /// assert_eq!(checksum, 0x41 + 0x42);
+    /// ```
+    pub fn code_units(&self) -> impl Iterator<Item = u16> + '_ {
+        self.as_slice().iter().copied()
+    }
+
+    /// Appends a Rust string slice to the end of the string, in place.
+    ///
+    /// The string slice is encoded as UTF-16 directly into the owned buffer. Any trailing NUL terminator is
+    /// dropped before appending, so the appended content always follows the logical content.
+    pub fn push_str(&mut self, s: &str) {
+        self.append(s.encode_utf16());
+    }
+
+    /// Appends a single character to the end of the string, in place.
+    ///
+    /// The character is encoded as one UTF-16 code unit, or as a surrogate pair for characters outside the Basic
+    /// Multilingual Plane.
+    pub fn push(&mut self, c: char) {
+        self.append(encode_chars([c]));
+    }
+
+    /// Appends raw UTF-16 code units to the end of the string, in place.
+    ///
+    /// This is the counterpart of [`push_str`](Self::push_str) for content that is already UTF-16, such as a
+    /// slice of another buffer, and avoids decoding and re-encoding it. The code units are copied as is without
+    /// validation. As with `push_str`, any trailing NUL terminator of the string is dropped first; NULs at the
+    /// end of `units` become the new terminator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would be longer than a `UNICODE_STRING` can describe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("Drive ");
+    /// my_string.append_wide(&[0x0043, 0x003A]);
+    /// assert!(my_string == OwnedUnicodeString::from("Drive C:"));
+    /// ```
+    pub fn append_wide(&mut self, units: &[u16]) {
+        self.append(units.iter().copied());
+    }
+
+    /// Pads the string with copies of `fill` until it is `width` code units long, in place.
+    ///
+    /// This is meant for fixed-layout records. `width` is measured in UTF-16 code units, like
+    /// [`len`](Self::len), not in characters: a `fill` outside the Basic Multilingual Plane advances the length by
+    /// two, and padding stops before it would exceed `width`, so the result can be one code unit short. Nothing is
+    /// appended if the string is already at least `width` code units long. As with `push_str`, any trailing NUL
+    /// terminator is dropped when padding is added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padded string would exceed `u16::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut field = OwnedUnicodeString::from("ab");
+    /// field.pad_to(5, ' ');
+    /// assert!(field == "ab   ");
+    /// ```
+    pub fn pad_to(&mut self, width: usize, fill: char) {
+        let count = width.saturating_sub(self.len()) / fill.len_utf16();
+        if count > 0 {
+            self.append(encode_chars(core::iter::repeat_n(fill, count)));
+        }
+    }
+
+    /// Inserts a Rust string slice at the code-unit index `code_unit_index`, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than the length of the string or falls in the middle of a surrogate pair.
+    /// See [`try_insert_str`](Self::try_insert_str) for a non-panicking variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut path = OwnedUnicodeString::from("\\Registry\\SYSTEM");
+    /// path.insert_str(10, "Machine\\");
+    /// assert!(path == OwnedUnicodeString::from("\\Registry\\Machine\\SYSTEM"));
+    /// ```
+    pub fn insert_str(&mut self, code_unit_index: usize, s: &str) {
+        if let Err(error) = self.try_insert_str(code_unit_index, s) {
+            panic!("failed to insert into OwnedUnicodeString at index {}: {:?}", code_unit_index, error);
+        }
+    }
+
+    /// Inserts a Rust string slice at the code-unit index `code_unit_index`, returning an error instead of
+    /// panicking.
+    ///
+    /// The string slice is encoded as UTF-16 and spliced into the buffer, after which the lengths are
+    /// recomputed and the `Buffer` pointer refreshed. A trailing NUL terminator, if any, is preserved.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::IndexOutOfBounds`] if the index is greater than the length of the string,
+    /// [`UnicodeStringError::SplitSurrogate`] if it falls between the two halves of a surrogate pair, and
+    /// [`UnicodeStringError::TooLong`] if the result would exceed `u16::MAX` bytes. The string is left unchanged
+    /// on error.
+    pub fn try_insert_str(&mut self, code_unit_index: usize, s: &str) -> Result<()> {
+        if code_unit_index > self.len() {
+            return Err(UnicodeStringError::IndexOutOfBounds);
+        }
+        if splits_surrogate_pair(self.as_slice(), code_unit_index) {
+            return Err(UnicodeStringError::SplitSurrogate);
+        }
+        let count = s.encode_utf16().count();
+        self.check_grow(count)?;
+        self.reserve_secret(count);
+        self.buffer.splice(code_unit_index..code_unit_index, s.encode_utf16());
+        self.compute_size()
+    }
+
+    /// Inserts a Rust string slice at the front of the string, in place.
+    ///
+    /// The UTF-16 encoding of `s` is spliced directly into the owned buffer, which avoids the temporary string
+    /// and copy of `OwnedUnicodeString::from(s) + self`. The `Buffer` pointer and lengths are updated afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would be longer than a `UNICODE_STRING` can describe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut path = OwnedUnicodeString::from("C:\\file");
+    /// path.prepend("\\??\\");
+    /// assert!(path == OwnedUnicodeString::from("\\??\\C:\\file"));
+    /// ```
+    pub fn prepend(&mut self, s: &str) {
+        self.insert_str(0, s);
+    }
+
+    /// Removes the code units in the range `[start, end)` from the string, in place.
+    ///
+    /// The indices are UTF-16 code units. Removing only one half of a surrogate pair leaves a lone surrogate
+    /// behind, which is then rendered as `�`. The lengths are recomputed and the `Buffer` pointer refreshed
+    /// afterwards, and a trailing NUL terminator, if any, is preserved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is greater than `end` or if `end` is greater than the length of the string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("abcdef");
+    /// my_string.remove_range(1, 4);
+    /// assert!(my_string == OwnedUnicodeString::from("aef"));
+    /// ```
+    pub fn remove_range(&mut self, start: usize, end: usize) {
+        let length = self.len();
+        assert!(
+            start <= end && end <= length,
+            "range {}..{} out of bounds for OwnedUnicodeString of length {}",
+            start,
+            end,
+            length
+        );
+        self.buffer.drain(start..end);
+        self.compute_size_or_panic();
+    }
+
+    /// Concatenates `rhs` onto the string, returning an error if the result would be too long.
+    ///
+    /// The combined byte length is checked against `u16::MAX` before the buffer is extended, so the `Length`
+    /// field can never wrap around to a small value while the buffer is huge. This is the checked counterpart of
+    /// the `Add` implementation, which panics in that case.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::TooLong`] if the combined content would exceed `u16::MAX` bytes. For a
+    /// string that [keeps its NUL terminator](Self::keep_null_terminator), the terminator counts towards the
+    /// limit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("\\Registry\\").try_concat(&OwnedUnicodeString::from("Machine"));
+    /// assert!(path.is_ok());
+    /// ```
+    pub fn try_concat(mut self, rhs: &OwnedUnicodeString) -> Result<OwnedUnicodeString> {
+        let bytes = (self.len() + rhs.len() + self.keep_terminator as usize) * size_of::<u16>();
+        if bytes > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes });
+        }
+        self.try_append(rhs.as_slice().iter().copied())?;
+        Ok(self)
+    }
+
+    /// Reserves capacity for at least `additional_code_units` more UTF-16 code units.
+    ///
+    /// Reserving ahead of a sequence of appends avoids repeated reallocations. Because the reallocation may move
+    /// the storage, the `Buffer` pointer of the internal `UNICODE_STRING` is refreshed afterwards.
+    pub fn reserve(&mut self, additional_code_units: usize) {
+        self.reserve_secret(additional_code_units);
+        self.buffer.reserve(additional_code_units);
+        self.refresh_buffer_pointer();
+    }
+
+    /// Tries to reserve capacity for at least `additional_code_units` more UTF-16 code units.
+    ///
+    /// This is the fallible counterpart of [`reserve`](Self::reserve) for contexts such as kernel drivers, where
+    /// running out of memory must be handled rather than aborting. On success the `Buffer` pointer is refreshed;
+    /// on failure the string is left unchanged. Secret strings are moved to the new allocation and the old one is
+    /// wiped, as with the other growing operations.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::AllocFailed`] if the allocator reports a failure or the requested capacity
+    /// overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::{OwnedUnicodeString, UnicodeStringError};
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("C:");
+    /// assert!(my_string.try_reserve(64).is_ok());
+    /// assert_eq!(my_string.try_reserve(usize::MAX), Err(UnicodeStringError::AllocFailed));
+    /// ```
+    pub fn try_reserve(&mut self, additional_code_units: usize) -> Result<()> {
+        let spare = self.buffer.capacity() - self.buffer.len();
+        if self.secret && spare < additional_code_units {
+            let capacity = self
+                .buffer
+                .len()
+                .checked_add(additional_code_units)
+                .ok_or(UnicodeStringError::AllocFailed)?
+                .max(self.buffer.capacity() * 2);
+            let mut moved = Vec::new();
+            moved.try_reserve_exact(capacity).map_err(|_| UnicodeStringError::AllocFailed)?;
+            moved.extend_from_slice(&self.buffer);
+            let mut old = mem::replace(&mut self.buffer, moved);
+            wipe_allocation(&mut old);
+        } else {
+            self.buffer
+                .try_reserve(additional_code_units)
+                .map_err(|_| UnicodeStringError::AllocFailed)?;
+        }
+        self.refresh_buffer_pointer();
+        Ok(())
+    }
+
+    /// Shrinks the capacity of the buffer as much as possible.
+    ///
+    /// This releases the excess capacity left behind after a string has been built up and then shortened, which
+    /// matters in low-memory driver contexts. Shrinking may move the allocation, so the `Buffer` pointer is
+    /// refreshed. `Length` and `MaximumLength` describe the buffer's contents rather than its capacity and are
+    /// therefore left unchanged.
+    pub fn shrink_to_fit(&mut self) {
+        if self.secret {
+            if self.buffer.capacity() > self.buffer.len() {
+                self.move_secret_to(self.buffer.len());
+            }
+            return;
+        }
+        self.buffer.shrink_to_fit();
+        self.refresh_buffer_pointer();
+    }
+
+    /// Exchanges the contents of two strings without copying their buffers.
+    ///
+    /// This is useful for double-buffering, such as swapping an in-progress string with a finalized one. The
+    /// buffers, their `UNICODE_STRING` descriptors and the secret and terminator-keeping modes all move together,
+    /// so secret content is still wiped by whichever instance ends up owning it. Each `Buffer` pointer is refreshed
+    /// afterwards to point at the instance's own, newly received vector.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut front = OwnedUnicodeString::from("old");
+    /// let mut back = OwnedUnicodeString::from("new");
+    /// front.swap(&mut back);
+    /// assert!(front == "new" && back == "old");
+    /// ```
+    pub fn swap(&mut self, other: &mut OwnedUnicodeString) {
+        mem::swap(self, other);
+        self.refresh_buffer_pointer();
+        other.refresh_buffer_pointer();
+    }
+
+    /// Replaces every lone surrogate code unit in the string with `U+FFFD`, returning the number of repairs made.
+    ///
+    /// `Display` already renders unpaired surrogates as the replacement character, but the buffer itself keeps
+    /// them. This method makes the repair persistent, so the buffer can safely be handed to APIs that reject
+    /// malformed UTF-16. Well-formed surrogate pairs are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from(vec![0x0041, 0xD800]);
+    /// assert_eq!(my_string.repair_surrogates(), 1);
+    /// assert_eq!(my_string.as_slice(), &[0x0041, 0xFFFD]);
+    /// ```
+    pub fn repair_surrogates(&mut self) -> usize {
+        let content = self.as_mut_slice();
+        let mut repairs = 0;
+        let mut index = 0;
+
+        while index < content.len() {
+            let unit = content[index];
+            if is_high_surrogate(unit) && content.get(index + 1).is_some_and(|&next| is_low_surrogate(next)) {
+                index += 2;
+                continue;
+            }
+            if is_high_surrogate(unit) || is_low_surrogate(unit) {
+                content[index] = char::REPLACEMENT_CHARACTER as u16;
+                repairs += 1;
+            }
+            index += 1;
+        }
+
+        self.compute_size_or_panic();
+        repairs
+    }
+
+    /// Replaces every unpaired surrogate in the string with `replacement`, in place.
+    ///
+    /// Afterwards the logical content is guaranteed to be well-formed UTF-16. This generalizes
+    /// [`repair_surrogates`](Self::repair_surrogates), which always substitutes `U+FFFD`. A `char` can never be a
+    /// surrogate, so any replacement is valid; one outside the Basic Multilingual Plane takes two code units and
+    /// therefore makes the string longer. The `Buffer` pointer and lengths are updated, and a NUL terminator
+    /// present before the call is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the repaired string would be longer than a `UNICODE_STRING` can describe. The string is left
+    /// unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from_wide(&[0x0041, 0xDC00, 0x0042]);
+    /// my_string.replace_invalid('?');
+    /// assert!(my_string == OwnedUnicodeString::from("A?B"));
+    /// ```
+    pub fn replace_invalid(&mut self, replacement: char) {
+        let mut repaired =
+            encode_chars(self.try_chars().map(|result| result.unwrap_or(replacement))).collect::<Vec<u16>>();
+        let terminated = self.is_null_terminated();
+        let bytes = (repaired.len() + self.keep_terminator as usize) * size_of::<u16>();
+        if bytes > MAX_BYTES {
+            panic!(
+                "failed to replace invalid UTF-16 in OwnedUnicodeString: {:?}",
+                UnicodeStringError::TooLong { bytes }
+            );
+        }
+
+        self.buffer.clear();
+        self.reserve_secret(repaired.len() + 1);
+        self.buffer.extend_from_slice(&repaired);
+        if terminated {
+            self.buffer.push(0);
+        }
+        if self.secret {
+            wipe_allocation(&mut repaired);
+        }
+        self.compute_size_or_panic();
+    }
+
+    /// Splits the string into two owned strings at the code-unit index `mid`.
+    ///
+    /// The first string contains the code units `[0, mid)` and the second the code units `[mid, len)`. Each half
+    /// is copied into its own buffer with its own `UNICODE_STRING`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the length of the string or if it falls in the middle of a surrogate
+    /// pair. See [`try_split_at`](Self::try_split_at) for a non-panicking variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let (key, value) = OwnedUnicodeString::from("Key\\Value").split_at(3);
+    /// ```
+    pub fn split_at(&self, mid: usize) -> (OwnedUnicodeString, OwnedUnicodeString) {
+        match self.try_split_at(mid) {
+            Ok(halves) => halves,
+            Err(error) => panic!("failed to split OwnedUnicodeString at index {}: {:?}", mid, error),
+        }
+    }
+
+    /// Splits the string into two owned strings at the code-unit index `mid`, returning an error instead of
+    /// panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::IndexOutOfBounds`] if `mid` is greater than the length of the string, and
+    /// [`UnicodeStringError::SplitSurrogate`] if `mid` falls between the two halves of a surrogate pair.
+    pub fn try_split_at(&self, mid: usize) -> Result<(OwnedUnicodeString, OwnedUnicodeString)> {
+        let slice = self.as_slice();
+        if mid > slice.len() {
+            return Err(UnicodeStringError::IndexOutOfBounds);
+        }
+        if splits_surrogate_pair(slice, mid) {
+            return Err(UnicodeStringError::SplitSurrogate);
+        }
+        let (left, right) = slice.split_at(mid);
+        Ok((Self::from(left.to_vec()), Self::from(right.to_vec())))
+    }
+
+    /// Returns an iterator over the segments of the string separated by `delimiter`.
+    ///
+    /// Each segment is copied into a new `OwnedUnicodeString`. As with `str::split`, consecutive delimiters
+    /// produce empty segments, a leading or trailing delimiter produces a leading or trailing empty segment,
+    /// and an empty string yields a single empty segment. The delimiter may be any character, including one
+    /// outside the Basic Multilingual Plane, in which case its two-code-unit surrogate pair is matched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("Registry\\Machine\\SYSTEM");
+    /// let segments: Vec<OwnedUnicodeString> = path.split('\\').collect();
+    /// assert_eq!(segments.len(), 3);
+    /// ```
+    pub fn split(&self, delimiter: char) -> impl Iterator<Item = OwnedUnicodeString> + '_ {
+        let mut units = [0u16; 2];
+        let delimiter_len = delimiter.encode_utf16(&mut units).len();
+        let mut rest = Some(self.as_slice());
+
+        core::iter::from_fn(move || {
+            let current = rest?;
+            match find_units(current, &units[..delimiter_len]) {
+                Some(index) => {
+                    rest = Some(&current[index + delimiter_len..]);
+                    Some(Self::from_wide(&current[..index]))
+                }
+                None => {
+                    rest = None;
+                    Some(Self::from_wide(current))
+                }
+            }
+        })
+    }
+
+    /// Returns an iterator over the lines of the string, without their line terminators.
+    ///
+    /// A line ends at `\n`, at `\r\n`, which counts as a single break, or at a lone `\r`, so text with mixed
+    /// Windows, Unix and classic Mac line endings is split consistently. Unlike `str::lines`, a lone `\r` is a
+    /// break too. As with `str::lines`, a final line terminator does not produce a trailing empty line, and an
+    /// empty string yields no lines. Each line is copied into a new `OwnedUnicodeString`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let inf = OwnedUnicodeString::from("[Version]\r\nSignature=\"$Windows NT$\"\r\n");
+    /// let lines: Vec<OwnedUnicodeString> = inf.lines().collect();
+    /// assert_eq!(lines.len(), 2);
+    /// assert!(lines[0] == OwnedUnicodeString::from("[Version]"));
+    /// ```
+    pub fn lines(&self) -> impl Iterator<Item = OwnedUnicodeString> + '_ {
+        const CR: u16 = b'\r' as u16;
+        const LF: u16 = b'\n' as u16;
+        let mut rest = self.as_slice();
+
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let current = rest;
+            match current.iter().position(|&unit| unit == CR || unit == LF) {
+                Some(index) => {
+                    let crlf = current[index] == CR && current.get(index + 1) == Some(&LF);
+                    rest = &current[index + if crlf { 2 } else { 1 }..];
+                    Some(Self::from_wide(&current[..index]))
+                }
+                None => {
+                    rest = &[];
+                    Some(Self::from_wide(current))
+                }
+            }
+        })
+    }
+
+    /// Splits the string at the last occurrence of `delimiter`, returning the parts before and after it.
+    ///
+    /// The delimiter itself is not part of either half, and each half is copied into a new
+    /// `OwnedUnicodeString`. Returns `None` if the delimiter does not occur. A trailing delimiter yields an empty
+    /// second part. As with [`split`](Self::split), a delimiter outside the Basic Multilingual Plane is matched as
+    /// a surrogate pair.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("C:\\Windows\\System32\\ntoskrnl.exe");
+    /// let (directory, file) = path.rsplit_once('\\').unwrap();
+    /// assert!(directory == OwnedUnicodeString::from("C:\\Windows\\System32"));
+    /// assert!(file == OwnedUnicodeString::from("ntoskrnl.exe"));
+    /// ```
+    pub fn rsplit_once(&self, delimiter: char) -> Option<(OwnedUnicodeString, OwnedUnicodeString)> {
+        let mut units = [0u16; 2];
+        let delimiter = delimiter.encode_utf16(&mut units);
+        let content = self.as_slice();
+        let index = content.windows(delimiter.len()).rposition(|window| window == delimiter)?;
+
+        Some((
+            Self::from_wide(&content[..index]),
+            Self::from_wide(&content[index + delimiter.len()..]),
+        ))
+    }
+
+    /// Removes leading and trailing ASCII whitespace from the string, in place.
+    ///
+    /// The code units stripped are space, tab, CR, LF, form feed and vertical tab. Non-ASCII whitespace is left
+    /// untouched. The buffer is NUL-terminated again after trimming and the `Buffer` pointer is refreshed. As with a
+    /// `PCWSTR` conversion, a terminator that does not fit in `MaximumLength` is still appended, and `MaximumLength`
+    /// is capped at the largest value a `UNICODE_STRING` can describe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut value = OwnedUnicodeString::from("  C:\\Windows\r\n");
+    /// value.trim();
+    /// assert!(value == OwnedUnicodeString::from("C:\\Windows"));
+    /// ```
+    pub fn trim(&mut self) {
+        let (start, end) = trimmed_bounds(self.as_slice());
+        self.buffer.truncate(end);
+        self.buffer.drain(..start);
+        self.compute_size_or_panic();
+        self.ensure_is_null_terminated();
+    }
+
+    /// Repeatedly removes `pat` from the end of the string until it no longer ends with it, in place.
+    ///
+    /// This is handy for stripping trailing separators, as in `path\\` becoming `path`. Matching is performed
+    /// on UTF-16 code units. An empty pattern leaves the string unchanged, and a pattern that makes up the whole
+    /// string leaves it empty. A NUL terminator present before the call is kept, and the lengths and `Buffer`
+    /// pointer are updated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut path = OwnedUnicodeString::from("C:\\Windows\\\\");
+    /// path.trim_end_matches("\\");
+    /// assert!(path == OwnedUnicodeString::from("C:\\Windows"));
+    /// ```
+    pub fn trim_end_matches(&mut self, pat: &str) {
+        let pattern = pat.encode_utf16().collect::<Vec<u16>>();
+        if pattern.is_empty() {
+            return;
+        }
+
+        let mut end = self.len();
+        while self.as_slice()[..end].ends_with(&pattern) {
+            end -= pattern.len();
+        }
+        self.retain_range(0, end);
+    }
+
+    /// Repeatedly removes `pat` from the start of the string until it no longer starts with it, in place.
+    ///
+    /// This behaves like [`trim_end_matches`](Self::trim_end_matches), working from the front of the string.
+    pub fn trim_start_matches(&mut self, pat: &str) {
+        let pattern = pat.encode_utf16().collect::<Vec<u16>>();
+        if pattern.is_empty() {
+            return;
+        }
+
+        let mut start = 0;
+        while self.as_slice()[start..].starts_with(&pattern) {
+            start += pattern.len();
+        }
+        self.retain_range(start, self.len());
+    }
+
+    /// Returns a new `OwnedUnicodeString` with leading and trailing ASCII whitespace removed.
+    ///
+    /// This is the non-mutating counterpart of [`trim`](Self::trim).
+    pub fn trimmed(&self) -> OwnedUnicodeString {
+        let (start, end) = trimmed_bounds(self.as_slice());
+        Self::from(self.as_slice()[start..end].to_vec())
+    }
+
+    /// Returns a new `OwnedUnicodeString` with every non-overlapping occurrence of `from` replaced by `to`.
+    ///
+    /// Matching is performed on UTF-16 code units, scanning from the start of the string. If `from` is empty,
+    /// the result is a plain copy of the string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("C:/Windows/System32");
+    /// assert!(path.replace("/", "\\") == OwnedUnicodeString::from("C:\\Windows\\System32"));
+    /// ```
+    pub fn replace(&self, from: &str, to: &str) -> OwnedUnicodeString {
+        let from = from.encode_utf16().collect::<Vec<u16>>();
+        let to = to.encode_utf16().collect::<Vec<u16>>();
+        let mut rest = self.as_slice();
+        if from.is_empty() {
+            return Self::from(rest.to_vec());
+        }
+
+        let mut result = Vec::with_capacity(rest.len());
+        while let Some(index) = find_units(rest, &from) {
+            result.extend_from_slice(&rest[..index]);
+            result.extend_from_slice(&to);
+            rest = &rest[index + from.len()..];
+        }
+        result.extend_from_slice(rest);
+        Self::from(result)
+    }
+
+    /// Retains only the characters for which `f` returns `true`, in place.
+    ///
+    /// The logical content is decoded character by character and the kept characters are re-encoded into the
+    /// same buffer, after which the lengths are recomputed and the `Buffer` pointer refreshed. Invalid sequences,
+    /// such as lone surrogates, are passed to `f` as the Unicode replacement character (`�`) and, if kept, are
+    /// stored as `U+FFFD`. Any trailing NUL terminator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("a1b2c3");
+    /// my_string.retain(|c| c.is_ascii_digit());
+    /// assert!(my_string == OwnedUnicodeString::from("123"));
+    /// ```
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        let length = self.len();
+        let mut read = 0;
+        let mut write = 0;
+
+        while read < length {
+            let (ch, width) = decode_at(&self.buffer[..length], read);
+            if f(ch) {
+                write += ch.encode_utf16(&mut self.buffer[write..read + width]).len();
+            }
+            read += width;
+        }
+
+        self.buffer.truncate(write);
+        self.compute_size_or_panic();
+    }
+
+    /// Reverses the order of the characters in the string, in place.
+    ///
+    /// The string is reversed by Unicode scalar value rather than by code unit, so surrogate pairs stay intact.
+    /// Lone surrogates are replaced with `U+FFFD`, which guarantees that two unpaired surrogates can never end
+    /// up forming a new pair after reversal. The length of the string does not change.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut my_string = OwnedUnicodeString::from("a𐐷b");
+    /// my_string.reverse();
+    /// assert!(my_string == OwnedUnicodeString::from("b𐐷a"));
+    /// ```
+    pub fn reverse(&mut self) {
+        let content = self.as_slice();
+        let mut reversed = alloc::vec![0u16; content.len()];
+        let mut index = 0;
+
+        while index < content.len() {
+            let (ch, width) = decode_at(content, index);
+            let end = content.len() - index;
+            ch.encode_utf16(&mut reversed[end - width..end]);
+            index += width;
+        }
+
+        self.as_mut_slice().copy_from_slice(&reversed);
+        if self.secret {
+            wipe_allocation(&mut reversed);
+        }
+    }
+
+    /// Returns a new `OwnedUnicodeString` containing the string repeated `n` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would be longer than a `UNICODE_STRING` can describe. See
+    /// [`try_repeat`](Self::try_repeat) for a non-panicking variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let separator = OwnedUnicodeString::from("-").repeat(4);
+    /// assert!(separator == OwnedUnicodeString::from("----"));
+    /// ```
+    pub fn repeat(&self, n: usize) -> OwnedUnicodeString {
+        match self.try_repeat(n) {
+            Ok(repeated) => repeated,
+            Err(error) => panic!("failed to repeat OwnedUnicodeString {} times: {:?}", n, error),
+        }
+    }
+
+    /// Returns a new `OwnedUnicodeString` containing the string repeated `n` times, returning an error instead
+    /// of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::TooLong`] if the repeated content would exceed `u16::MAX` bytes.
+    pub fn try_repeat(&self, n: usize) -> Result<OwnedUnicodeString> {
+        let content = self.as_slice();
+        let bytes = size_of_val(content).saturating_mul(n);
+        if bytes > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes });
+        }
+        Ok(Self::from(content.repeat(n)))
+    }
+
+    /// Concatenates `parts` into a new `OwnedUnicodeString`, placing `sep` between each pair of parts.
+    ///
+    /// This mirrors `slice::join`: an empty slice yields an empty string and a single part is copied as is. The
+    /// logical content of each part is used, so trailing NUL terminators are not carried into the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result would be longer than a `UNICODE_STRING` can describe, like the `Add` operators. See
+    /// [`try_join`](Self::try_join) for a non-panicking variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let parts = [OwnedUnicodeString::from("Registry"), OwnedUnicodeString::from("Machine")];
+    /// let path = OwnedUnicodeString::join(&parts, "\\");
+    /// assert!(path == OwnedUnicodeString::from("Registry\\Machine"));
+    /// ```
+    pub fn join(parts: &[OwnedUnicodeString], sep: &str) -> OwnedUnicodeString {
+        match Self::try_join(parts, sep) {
+            Ok(joined) => joined,
+            Err(error) => panic!("failed to join OwnedUnicodeString parts: {:?}", error),
+        }
+    }
+
+    /// Concatenates `parts` with `sep` between them, returning an error instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::TooLong`] if the joined content would exceed `u16::MAX` bytes.
+    pub fn try_join(parts: &[OwnedUnicodeString], sep: &str) -> Result<OwnedUnicodeString> {
+        let separator: Vec<u16> = sep.encode_utf16().collect();
+        let code_units = parts.iter().map(OwnedUnicodeString::len).sum::<usize>()
+            + separator.len() * parts.len().saturating_sub(1);
+        let bytes = code_units * size_of::<u16>();
+        if bytes > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes });
+        }
+
+        let mut buffer = Vec::with_capacity(code_units);
+        for (index, part) in parts.iter().enumerate() {
+            if index > 0 {
+                buffer.extend_from_slice(&separator);
+            }
+            buffer.extend_from_slice(part.as_slice());
+        }
+        Self::try_from_vec(buffer)
+    }
+
+    /// Converts the ASCII letters `a`-`z` in the string to `A`-`Z`, in place.
+    ///
+    /// Non-ASCII code units are left untouched. The conversion never changes the length of the string, so no
+    /// reallocation takes place and the trailing NUL terminator, if any, is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut drive = OwnedUnicodeString::from("c:");
+    /// drive.make_ascii_uppercase();
+    /// assert!(drive == OwnedUnicodeString::from("C:"));
+    /// ```
+    pub fn make_ascii_uppercase(&mut self) {
+        for unit in self.as_mut_slice() {
+            *unit = to_ascii_uppercase(*unit);
+        }
+    }
+
+    /// Converts the ASCII letters `A`-`Z` in the string to `a`-`z`, in place.
+    ///
+    /// Non-ASCII code units are left untouched. The conversion never changes the length of the string, so no
+    /// reallocation takes place and the trailing NUL terminator, if any, is preserved.
+    pub fn make_ascii_lowercase(&mut self) {
+        for unit in self.as_mut_slice() {
+            *unit = to_ascii_lowercase(*unit);
+        }
+    }
+
+    /// Replaces every forward slash `/` in the string with a backslash `\`, in place.
+    ///
+    /// Windows paths use backslashes, but user-supplied paths often contain forward slashes. Both are single code
+    /// units, so the length does not change and no reallocation takes place.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut path = OwnedUnicodeString::from("C:/Users/foo");
+    /// path.normalize_separators();
+    /// assert!(path == OwnedUnicodeString::from("C:\\Users\\foo"));
+    /// ```
+    pub fn normalize_separators(&mut self) {
+        for unit in self.as_mut_slice() {
+            if *unit == b'/' as u16 {
+                *unit = b'\\' as u16;
+            }
+        }
+    }
+
+    /// Returns a copy of the string with the ASCII letters `a`-`z` converted to `A`-`Z`.
+    ///
+    /// This is the non-mutating counterpart of [`make_ascii_uppercase`](Self::make_ascii_uppercase), mirroring
+    /// `str::to_ascii_uppercase`. The original is left untouched and non-ASCII code units are copied as is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let name = OwnedUnicodeString::from("ntoskrnl.exe");
+    /// assert!(name.to_ascii_uppercase() == OwnedUnicodeString::from("NTOSKRNL.EXE"));
+    /// ```
+    pub fn to_ascii_uppercase(&self) -> OwnedUnicodeString {
+        Self::from(self.code_units().map(to_ascii_uppercase).collect::<Vec<u16>>())
+    }
+
+    /// Returns a copy of the string with the ASCII letters `A`-`Z` converted to `a`-`z`.
+    ///
+    /// This is the non-mutating counterpart of [`make_ascii_lowercase`](Self::make_ascii_lowercase). The
+    /// original is left untouched and non-ASCII code units are copied as is.
+    pub fn to_ascii_lowercase(&self) -> OwnedUnicodeString {
+        Self::from(self.code_units().map(to_ascii_lowercase).collect::<Vec<u16>>())
+    }
+
+    /// Returns an iterator over the characters of the string.
+    ///
+    /// The logical content is decoded directly from the UTF-16 buffer without building a `String`. Invalid
+    /// sequences, such as lone surrogates, are yielded as the Unicode replacement character (`�`), matching the
+    /// behavior of `Display`. See [`try_chars`](Self::try_chars) to observe decoding errors instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("aé");
+    /// assert!(my_string.chars().eq(['a', 'é']));
+    /// ```
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.try_chars().map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Returns `true` if `f` returns `true` for every character of the string.
+    ///
+    /// This supports validation such as checking that a name only contains allowed characters. The content is
+    /// decoded lazily and the check stops at the first character that fails. Unpaired surrogates are passed to
+    /// `f` as the replacement character (`�`), so a strict allowlist rejects malformed UTF-16. An empty string
+    /// passes any predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("\\Registry\\Machine\\SYSTEM");
+    /// assert!(path.all_chars(|c| c.is_alphanumeric() || c == '\\'));
+    /// ```
+    pub fn all_chars<F>(&self, f: F) -> bool
+    where
+        F: FnMut(char) -> bool,
+    {
+        self.chars().all(f)
+    }
+
+    /// Returns `true` if `f` returns `true` for at least one character of the string.
+    ///
+    /// The content is decoded lazily and the check stops at the first matching character. As with
+    /// [`all_chars`](Self::all_chars), unpaired surrogates are passed to `f` as the replacement character. An empty
+    /// string matches no predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let name = OwnedUnicodeString::from("file?.txt");
+    /// assert!(name.any_char(|c| matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*')));
+    /// ```
+    pub fn any_char<F>(&self, f: F) -> bool
+    where
+        F: FnMut(char) -> bool,
+    {
+        self.chars().any(f)
+    }
+
+    /// Returns the Unicode scalar value at position `scalar_index`, or `None` if the string is shorter.
+    ///
+    /// The index counts characters, not code units: a surrogate pair occupies a single index. UTF-16 is not
+    /// randomly accessible by character, so the string is decoded from the start and each call is O(n). A lone
+    /// surrogate counts as one character and is returned as `U+FFFD`, as in [`chars`](Self::chars).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("a𐐷b");
+    /// assert_eq!(my_string.char_at(1), Some('𐐷'));
+    /// assert_eq!(my_string.char_at(3), None);
+    /// ```
+    pub fn char_at(&self, scalar_index: usize) -> Option<char> {
+        self.chars().nth(scalar_index)
+    }
+
+    /// Returns an iterator over the characters of the string, yielding an error for each invalid sequence.
+    pub fn try_chars(&self) -> impl Iterator<Item = core::result::Result<char, DecodeUtf16Error>> + '_ {
+        decode_utf16(self.as_slice().iter().copied())
+    }
+
+    /// Compares the string with a raw `UNICODE_STRING`, such as one handed over by the kernel.
+    ///
+    /// The logical content of `self` is compared code unit by code unit against the `Length` bytes pointed to
+    /// by `other.Buffer`. This is exposed as an `unsafe` method rather than a `PartialEq` implementation because
+    /// reading `other`'s buffer cannot be checked.
+    ///
+    /// # Safety
+    ///
+    /// If `other.Length` is not zero, `other.Buffer` must be valid for reads of `other.Length` bytes and
+    /// suitably aligned for `u16`. The buffer must not be mutated for the duration of the call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("Hi");
+    /// let raw = *my_string.as_ref();
+    /// assert!(unsafe { my_string.eq_raw(&raw) });
+    /// ```
+    pub unsafe fn eq_raw(&self, other: &UNICODE_STRING) -> bool {
+        let other_length = other.Length as usize / size_of::<u16>();
+        if other_length == 0 {
+            return self.is_empty();
+        }
+        self.as_slice() == unsafe { slice::from_raw_parts(other.Buffer, other_length) }
+    }
+
+    /// Compares two strings with `RtlEqualUnicodeString`, optionally ignoring case.
+    ///
+    /// This gives the exact semantics of the Windows kernel and native APIs: case-insensitive comparison uses the
+    /// operating system's uppercase tables, which may differ from the crate's own ASCII and case-folding
+    /// comparisons. Only the logical content described by `Length` is compared, so trailing NUL terminators are
+    /// ignored.
+    ///
+    /// Both strings are taken by shared reference rather than `&mut`: `RtlEqualUnicodeString` receives
+    /// `*const UNICODE_STRING` pointers and never writes through them, so a mutable borrow would only stop callers
+    /// from comparing a string with itself or while it is borrowed elsewhere. Callers that hold `&mut` can still
+    /// pass it, since it coerces to `&`.
+    ///
+    /// This method requires the `nt-rtl` feature, which links against the NT runtime library. It is kept behind a
+    /// feature so that `no_std` builds outside the kernel are not forced to import it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(windows)]
+    /// # {
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let name = OwnedUnicodeString::from("NTOSKRNL.EXE");
+    /// assert!(name.nt_equals(&OwnedUnicodeString::from("ntoskrnl.exe"), true));
+    /// assert!(!name.nt_equals(&OwnedUnicodeString::from("ntoskrnl.exe"), false));
+    /// # }
+    /// ```
+    #[cfg(feature = "nt-rtl")]
+    pub fn nt_equals(&self, other: &OwnedUnicodeString, case_insensitive: bool) -> bool {
+        use windows_sys::Wdk::System::SystemServices::RtlEqualUnicodeString;
+
+        unsafe { RtlEqualUnicodeString(&self.unicode_string, &other.unicode_string, case_insensitive as u8) != 0 }
+    }
+
+    /// Returns `true` if the logical content of the string contains a NUL code unit.
+    ///
+    /// Trailing NUL terminators are not part of the logical content and are not reported. An embedded NUL is
+    /// valid in a `UNICODE_STRING`, but it cannot be represented in a NUL-terminated `PCWSTR` or `PWSTR`: C code
+    /// reading such a pointer stops at the first NUL.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::from("a\0b").contains_embedded_nul());
+    /// assert!(!OwnedUnicodeString::from("ab\0").contains_embedded_nul());
+    /// ```
+    pub fn contains_embedded_nul(&self) -> bool {
+        self.as_slice().contains(&0)
+    }
+
+    /// Converts the string into a `PCWSTR`, returning an error if it contains an embedded NUL.
+    ///
+    /// This is the checked counterpart of the `From<&mut OwnedUnicodeString>` conversion. Strings coming from
+    /// untrusted sources may legitimately hold NULs that a `PCWSTR` cannot represent; rejecting them avoids
+    /// silently passing a truncated string to C code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::EmbeddedNul`] with the index of the first embedded NUL.
+    pub fn try_into_pcwstr(&mut self) -> Result<PCWSTR> {
+        self.check_no_embedded_nul()?;
+        Ok(PCWSTR::from(self))
+    }
+
+    /// Returns a `PCWSTR` to the buffer if it is already NUL-terminated, without modifying it.
+    ///
+    /// Unlike the `From<&mut OwnedUnicodeString>` conversion, this never appends a terminator and therefore only
+    /// needs `&self`, which lets read-only holders obtain a pointer. It returns `None` when the buffer does not
+    /// end with a NUL; strings created with [`new_terminated`](Self::new_terminated) or converted to a `PCWSTR`
+    /// before always do. As with the other conversions, C code stops reading at an embedded NUL.
+    ///
+    /// The pointer stays valid until the string is next modified or dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::new_terminated("C:").as_pcwstr().is_some());
+    /// assert!(OwnedUnicodeString::from("C:").as_pcwstr().is_none());
+    /// ```
+    pub fn as_pcwstr(&self) -> Option<PCWSTR> {
+        if self.is_null_terminated() {
+            Some(self.buffer.as_ptr())
+        } else {
+            None
+        }
+    }
+
+    /// Converts the string into a `PWSTR`, returning an error if it contains an embedded NUL.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::EmbeddedNul`] with the index of the first embedded NUL.
+    pub fn try_into_pwstr(&mut self) -> Result<PWSTR> {
+        self.check_no_embedded_nul()?;
+        Ok(PWSTR::from(self))
+    }
+
+    /// Returns `true` if the string contains `needle`.
+    ///
+    /// The needle is encoded as UTF-16 and searched for as a code-unit subsequence of the logical content, so a
+    /// match can never span the trailing NUL terminator. An empty needle is always contained.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("\\Device\\HarddiskVolume1\\Windows");
+    /// assert!(path.contains("HarddiskVolume1"));
+    /// ```
+    pub fn contains(&self, needle: &str) -> bool {
+        self.find(needle).is_some()
+    }
+
+    /// Returns the index of the first occurrence of `needle` in the string, or `None` if it is absent.
+    ///
+    /// The returned index is expressed in UTF-16 code units, not in bytes or Unicode scalar values, so it can be
+    /// used directly to index [`as_slice`](Self::as_slice). An empty needle is found at index `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let key = OwnedUnicodeString::from("Key\\Value");
+    /// assert_eq!(key.find("\\"), Some(3));
+    /// ```
+    pub fn find(&self, needle: &str) -> Option<usize> {
+        let needle = needle.encode_utf16().collect::<Vec<u16>>();
+        find_units(self.as_slice(), &needle)
+    }
+
+    /// Returns the number of non-overlapping occurrences of `needle` in the string.
+    ///
+    /// The search scans the logical content from left to right like [`find`](Self::find) and resumes after the
+    /// end of each match, so overlapping matches are not counted twice: `"aa"` occurs once in `"aaa"`. An empty
+    /// needle returns `0`, since counting empty matches is rarely useful for validation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("\\Registry\\Machine\\SYSTEM");
+    /// assert_eq!(path.matches_count("\\"), 3);
+    /// ```
+    pub fn matches_count(&self, needle: &str) -> usize {
+        let needle = needle.encode_utf16().collect::<Vec<u16>>();
+        if needle.is_empty() {
+            return 0;
+        }
+
+        let mut haystack = self.as_slice();
+        let mut count = 0;
+        while let Some(index) = find_units(haystack, &needle) {
+            count += 1;
+            haystack = &haystack[index + needle.len()..];
+        }
+        count
+    }
+
+    /// Returns `true` if the string starts with `prefix`.
+    ///
+    /// The prefix is encoded as UTF-16 and compared against the beginning of the logical content. An empty prefix
+    /// always matches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let path = OwnedUnicodeString::from("\\Device\\HarddiskVolume1");
+    /// assert!(path.starts_with("\\Device\\"));
+    /// ```
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        let slice = self.as_slice();
+        prefix.encode_utf16().count() <= slice.len()
+            && slice.iter().zip(prefix.encode_utf16()).all(|(&a, b)| a == b)
+    }
+
+    /// Returns `true` if the string ends with `suffix`.
+    ///
+    /// The suffix is compared against the end of the logical content, so any trailing NUL terminator stored in
+    /// the buffer is ignored. An empty suffix always matches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::from("foo.txt").ends_with(".txt"));
+    /// ```
+    pub fn ends_with(&self, suffix: &str) -> bool {
+        self.ends_with_by(suffix, |a, b| a == b)
+    }
+
+    /// Returns a copy of the string with `prefix` removed, or `None` if the string does not start with it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let nt_path = OwnedUnicodeString::from("\\??\\C:\\Windows");
+    /// let dos_path = nt_path.strip_prefix("\\??\\").unwrap();
+    /// assert!(dos_path == OwnedUnicodeString::from("C:\\Windows"));
+    /// ```
+    pub fn strip_prefix(&self, prefix: &str) -> Option<OwnedUnicodeString> {
+        if !self.starts_with(prefix) {
+            return None;
+        }
+        let prefix_len = prefix.encode_utf16().count();
+        Some(Self::from_wide(&self.as_slice()[prefix_len..]))
+    }
+
+    /// Returns a copy of the string with `suffix` removed, or `None` if the string does not end with it.
+    pub fn strip_suffix(&self, suffix: &str) -> Option<OwnedUnicodeString> {
+        if !self.ends_with(suffix) {
+            return None;
+        }
+        let slice = self.as_slice();
+        let suffix_len = suffix.encode_utf16().count();
+        Some(Self::from_wide(&slice[..slice.len() - suffix_len]))
+    }
+
+    /// Converts an NT object path into a Win32 extended-length path.
+    ///
+    /// A leading `\??\` prefix is replaced with `\\?\`, so `\??\C:\foo` becomes `\\?\C:\foo`, which Win32
+    /// file APIs accept without further path normalization. If the string does not start with `\??\`, this is a
+    /// no-op and an unchanged copy is returned. See [`to_nt_object`](Self::to_nt_object) for the reverse
+    /// conversion.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let nt_path = OwnedUnicodeString::from(r"\??\C:\Windows");
+    /// assert!(nt_path.to_win32_extended() == r"\\?\C:\Windows");
+    /// ```
+    pub fn to_win32_extended(&self) -> OwnedUnicodeString {
+        self.swap_prefix("\\??\\", "\\\\?\\")
+    }
+
+    /// Converts a Win32 extended-length path into an NT object path.
+    ///
+    /// A leading `\\?\` prefix is replaced with `\??\`, so `\\?\C:\foo` becomes `\??\C:\foo`, which can be
+    /// passed to native APIs such as `NtCreateFile`. If the string does not start with `\\?\`, this is a no-op and
+    /// an unchanged copy is returned. See [`to_win32_extended`](Self::to_win32_extended) for the reverse
+    /// conversion.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let win32_path = OwnedUnicodeString::from(r"\\?\C:\Windows");
+    /// assert!(win32_path.to_nt_object() == r"\??\C:\Windows");
+    /// ```
+    pub fn to_nt_object(&self) -> OwnedUnicodeString {
+        self.swap_prefix("\\\\?\\", "\\??\\")
+    }
+
+    /// Returns the index of the first suffix in `suffixes` that the string ends with, or `None`.
+    ///
+    /// The comparison is performed on UTF-16 code units and is case-sensitive. See
+    /// [`ends_with_any_ignore_case`](Self::ends_with_any_ignore_case) for an ASCII case-insensitive variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let file = OwnedUnicodeString::from("driver.log");
+    /// assert_eq!(file.ends_with_any(&[".txt", ".log"]), Some(1));
+    /// ```
+    pub fn ends_with_any(&self, suffixes: &[&str]) -> Option<usize> {
+        suffixes
+            .iter()
+            .position(|suffix| self.ends_with(suffix))
+    }
+
+    /// Returns the index of the first suffix in `suffixes` that the string ends with, ignoring ASCII case.
+    ///
+    /// Only the ASCII letters `A`-`Z` and `a`-`z` are folded; all other code units must match exactly.
+    pub fn ends_with_any_ignore_case(&self, suffixes: &[&str]) -> Option<usize> {
+        suffixes
+            .iter()
+            .position(|suffix| self.ends_with_by(suffix, eq_ignore_ascii_case))
+    }
+
+    /// Compares two strings for equality using Unicode simple case folding.
+    ///
+    /// Unlike the ASCII-only comparisons, this also treats letters such as Greek `Σ`, `σ` and `ς` or Cyrillic
+    /// `Д` and `д` as equal. The folding is *simple*: every character folds to exactly one character, so `ß` does
+    /// not equal `SS`. Unpaired surrogates are compared by value and never equal a valid character.
+    ///
+    /// This method requires the `unicode-case` feature, which bundles a case-folding table of about 3 KiB.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let key = OwnedUnicodeString::from("ΣΥΣΤΗΜΑ");
+    /// assert!(key.eq_ignore_case(&OwnedUnicodeString::from("συστημα")));
+    /// assert!(!key.eq_ignore_case(&OwnedUnicodeString::from("σύστημα")));
+    /// ```
+    #[cfg(feature = "unicode-case")]
+    pub fn eq_ignore_case(&self, other: &OwnedUnicodeString) -> bool {
+        let fold = |result: core::result::Result<char, DecodeUtf16Error>| match result {
+            Ok(c) => Ok(case_folding::fold(c)),
+            Err(error) => Err(error.unpaired_surrogate()),
+        };
+        self.try_chars().map(fold).eq(other.try_chars().map(fold))
+    }
+
+    /// Returns a copy of the string with every character converted to lowercase using Unicode simple case mapping.
+    ///
+    /// Unlike [`to_ascii_lowercase`](Self::to_ascii_lowercase), this also converts letters outside ASCII, such as
+    /// Greek `Σ` or Cyrillic `Д`, in every plane. The mapping is *simple*: each character maps to exactly one
+    /// character regardless of context, so the final-sigma rule is not applied and `İ` (U+0130) becomes a plain
+    /// `i`. Because no simple mapping crosses into another plane, the result has the same length in code units.
+    /// Unpaired surrogates are copied unchanged.
+    ///
+    /// This method requires the `unicode-case` feature, which bundles lowercase and uppercase mapping tables of
+    /// about 6 KiB in addition to the case-folding table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::from("ΣΥΣΤΗΜΑ Москва").to_lowercase_full() == "συστημα москва");
+    /// ```
+    #[cfg(feature = "unicode-case")]
+    pub fn to_lowercase_full(&self) -> OwnedUnicodeString {
+        self.map_chars(case_mapping::to_lower)
+    }
+
+    /// Returns a copy of the string with every character converted to uppercase using Unicode simple case mapping.
+    ///
+    /// This is the uppercase counterpart of [`to_lowercase_full`](Self::to_lowercase_full). Special casing is not
+    /// applied, so `ß` stays `ß` instead of becoming `SS`, and characters such as `ǰ` that only have a
+    /// multi-character uppercase form are left unchanged. The result has the same length in code units, and
+    /// unpaired surrogates are copied unchanged.
+    ///
+    /// This method requires the `unicode-case` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::from("straße").to_uppercase_full() == "STRAßE");
+    /// ```
+    #[cfg(feature = "unicode-case")]
+    pub fn to_uppercase_full(&self) -> OwnedUnicodeString {
+        self.map_chars(case_mapping::to_upper)
+    }
+
+    /// Returns the number of terminal columns the string occupies when displayed.
+    ///
+    /// Characters with the East Asian Width property Wide or Fullwidth, such as CJK ideographs, kana and most
+    /// emoji, count as two columns and every other character as one. Unpaired surrogates are displayed as the
+    /// replacement character and count as one column. Control characters and combining marks are not treated
+    /// specially, so the result is only exact for text made of ordinary printable characters.
+    ///
+    /// Unlike the `Display` width and precision, which count characters, this accounts for how wide text is
+    /// rendered, which makes it suitable for padding columns of mixed-script output.
+    ///
+    /// This method requires the `width` feature, which bundles a width table of about 1 KiB.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert_eq!(OwnedUnicodeString::from("ab").display_width(), 2);
+    /// assert_eq!(OwnedUnicodeString::from("你好").display_width(), 4);
+    /// ```
+    #[cfg(feature = "width")]
+    pub fn display_width(&self) -> usize {
+        self.chars().map(east_asian_width::width).sum()
+    }
+}
+
+/// Overwrites the whole allocation of `buffer` with zeros, including its spare capacity.
+///
+/// Spare capacity can still hold content that was removed or truncated, so the wipe is not limited to the
+/// initialized part. Volatile stores followed by a compiler fence keep the compiler from eliding the writes because
+/// the memory is about to be freed.
+fn wipe_allocation(buffer: &mut Vec<u16>) {
+    let start = buffer.as_mut_ptr();
+    for offset in 0..buffer.capacity() {
+        // SAFETY: `offset` is within the allocation owned by `buffer`, and `u16` has no invalid bit patterns, so
+        // writing to the uninitialized spare capacity is sound.
+        unsafe { ptr::write_volatile(start.add(offset), 0) };
+    }
+    compiler_fence(AtomicOrdering::SeqCst);
+}
+
+fn encode_chars<I>(chars: I) -> impl Iterator<Item = u16>
+where
+    I: IntoIterator<Item = char>,
+{
+    chars.into_iter().flat_map(|ch| {
+        let mut units = [0u16; 2];
+        let count = ch.encode_utf16(&mut units).len();
+        units.into_iter().take(count)
+    })
+}
+
+fn find_units(haystack: &[u16], needle: &[u16]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn is_ascii_whitespace(unit: u16) -> bool {
+    matches!(unit, 0x0009..=0x000D | 0x0020)
+}
+
+fn trimmed_bounds(slice: &[u16]) -> (usize, usize) {
+    let start = slice
+        .iter()
+        .position(|&unit| !is_ascii_whitespace(unit))
+        .unwrap_or(slice.len());
+    let end = slice
+        .iter()
+        .rposition(|&unit| !is_ascii_whitespace(unit))
+        .map_or(start, |index| index + 1);
+    (start, end)
+}
+
+fn splits_surrogate_pair(slice: &[u16], index: usize) -> bool {
+    index > 0
+        && index < slice.len()
+        && is_high_surrogate(slice[index - 1])
+        && is_low_surrogate(slice[index])
+}
+
+fn decode_at(slice: &[u16], index: usize) -> (char, usize) {
+    let unit = slice[index];
+    if is_high_surrogate(unit) {
+        if let Some(&next) = slice.get(index + 1).filter(|&&next| is_low_surrogate(next)) {
+            let code_point = 0x10000 + (((unit as u32 - 0xD800) << 10) | (next as u32 - 0xDC00));
+            return (char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER), 2);
+        }
+    }
+    (char::from_u32(unit as u32).unwrap_or(char::REPLACEMENT_CHARACTER), 1)
+}
+
+fn is_high_surrogate(unit: u16) -> bool {
+    (0xD800..=0xDBFF).contains(&unit)
+}
+
+fn is_low_surrogate(unit: u16) -> bool {
+    (0xDC00..=0xDFFF).contains(&unit)
+}
+
+fn to_ascii_lowercase(unit: u16) -> u16 {
+    if (b'A' as u16..=b'Z' as u16).contains(&unit) {
+        unit + (b'a' - b'A') as u16
+    } else {
+        unit
+    }
+}
+
+fn to_ascii_uppercase(unit: u16) -> u16 {
+    if (b'a' as u16..=b'z' as u16).contains(&unit) {
+        unit - (b'a' - b'A') as u16
+    } else {
+        unit
+    }
+}
+
+fn eq_ignore_ascii_case(a: u16, b: u16) -> bool {
+    to_ascii_lowercase(a) == to_ascii_lowercase(b)
+}
+
+impl From<Vec<u16>> for OwnedUnicodeString {
+    /// Converts a `Vec<u16>` to an `OwnedUnicodeString`.
+    ///
+    /// This implementation takes ownership of the provided `Vec<u16>`, allowing for direct manipulation
+    /// of the UTF-16 buffer. It initializes an `UNICODE_STRING` with the provided vector, calculates
+    /// the length and maximum length of the buffer, and ensures that it remains valid and properly
+    /// managed throughout the instance's lifetime.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the input `Vec<u16>` represents a valid UTF-16 encoded string.
+    /// This function will calculate the lengths based on the vector's contents and adjust the
+    /// `UNICODE_STRING` fields accordingly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is longer than the `u16` length fields of a `UNICODE_STRING` can describe. See
+    /// [`OwnedUnicodeString::try_from_vec`] for a non-panicking variant.
+    fn from(value: Vec<u16>) -> Self {
+        match Self::try_from_vec(value) {
+            Ok(result) => result,
+            Err(error) => panic!("failed to create OwnedUnicodeString: {:?}", error),
+        }
+    }
+}
+
+impl TryFrom<&[u16]> for OwnedUnicodeString {
+    type Error = UnicodeStringError;
+
+    /// Copies a UTF-16 slice into an `OwnedUnicodeString` after checking that it is well-formed.
+    ///
+    /// Unlike [`OwnedUnicodeString::from_wide`], which accepts any code units, this rejects unpaired surrogates,
+    /// so the resulting string always decodes without replacement characters. As with `from_wide`, trailing NULs
+    /// are treated as terminators.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::InvalidUtf16`] with the index of the first unpaired surrogate, or
+    /// [`UnicodeStringError::TooLong`] if the slice is longer than `u16::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::{OwnedUnicodeString, UnicodeStringError};
+    ///
+    /// assert!(OwnedUnicodeString::try_from(&[0x0041, 0xD801, 0xDC37][..]).is_ok());
+    /// assert_eq!(
+    ///     OwnedUnicodeString::try_from(&[0x0041, 0xDC37][..]).err(),
+    ///     Some(UnicodeStringError::InvalidUtf16 { index: 1 })
+    /// );
+    /// ```
+    fn try_from(value: &[u16]) -> Result<Self> {
+        let mut index = 0;
+        for result in decode_utf16(value.iter().copied()) {
+            match result {
+                Ok(ch) => index += ch.len_utf16(),
+                Err(_) => return Err(UnicodeStringError::InvalidUtf16 { index }),
+            }
+        }
+        Self::try_from_vec(value.to_vec())
+    }
+}
+
+impl Default for OwnedUnicodeString {
+    /// Creates an empty `OwnedUnicodeString`, equivalent to [`OwnedUnicodeString::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for OwnedUnicodeString {
+    /// Converts a Rust string slice (`&str`) to an `OwnedUnicodeString`.
+    ///
+    /// This implementation encodes the Rust string as UTF-16 and stores the result in a `Vec<u16>`,
+    /// which is then used to initialize the `OwnedUnicodeString`. This allows for seamless integration
+    /// with Rust's native string types while leveraging the safety and efficiency of UTF-16 buffers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("Hello, world!");
+    /// ```
+    fn from(value: &str) -> Self {
+        Self::from(value.encode_utf16().collect::<Vec<u16>>())
+    }
+}
+
+impl From<String> for OwnedUnicodeString {
+    /// Converts an owned Rust `String` to an `OwnedUnicodeString`, consuming it.
+    ///
+    /// UTF-8 and UTF-16 buffers cannot be shared, so the content is encoded into a new `Vec<u16>` and the
+    /// `String` is dropped afterwards. Taking the `String` by value signals that the original is no longer needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let name = format!("\\Device\\Volume{}", 3);
+    /// let my_string = OwnedUnicodeString::from(name);
+    /// assert_eq!(my_string.len(), 15);
+    /// ```
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl From<char> for OwnedUnicodeString {
+    /// Creates a one-character `OwnedUnicodeString`, such as a path separator.
+    ///
+    /// The character is encoded as one UTF-16 code unit, or as a surrogate pair for characters outside the Basic
+    /// Multilingual Plane. As with every other constructor, a trailing NUL is a terminator, so `'\0'` yields an
+    /// empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let separator = OwnedUnicodeString::from('\\');
+    /// assert_eq!(separator.as_slice(), &[0x005C]);
+    /// ```
+    fn from(value: char) -> Self {
+        let mut units = [0u16; 2];
+        Self::from_wide(value.encode_utf16(&mut units))
+    }
+}
+
+impl From<&[&str]> for OwnedUnicodeString {
+    /// Concatenates string fragments into a single `OwnedUnicodeString`.
+    ///
+    /// The UTF-16 lengths of all fragments are summed first, so the buffer is allocated exactly once. No
+    /// separator is inserted between fragments; use [`OwnedUnicodeString::join`] for that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the concatenated fragments are longer than a `UNICODE_STRING` can describe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from(&["Hello, ", "world", "!"][..]);
+    /// assert!(my_string == OwnedUnicodeString::from("Hello, world!"));
+    /// ```
+    fn from(value: &[&str]) -> Self {
+        let code_units = value.iter().map(|fragment| fragment.encode_utf16().count()).sum();
+        let mut buffer = Vec::with_capacity(code_units);
+        for fragment in value {
+            buffer.extend(fragment.encode_utf16());
+        }
+        Self::from(buffer)
+    }
+}
+
+impl FromIterator<char> for OwnedUnicodeString {
+    /// Builds an `OwnedUnicodeString` from an iterator of characters.
+    ///
+    /// Each character is encoded to one or two UTF-16 code units through a small stack buffer, so characters
+    /// outside the Basic Multilingual Plane are stored as proper surrogate pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string: OwnedUnicodeString = "a b c".chars().filter(|c| !c.is_whitespace()).collect();
+    /// ```
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        Self::from(encode_chars(iter).collect::<Vec<u16>>())
+    }
+}
+
+impl Extend<char> for OwnedUnicodeString {
+    /// Appends each character of the iterator to the `OwnedUnicodeString`, in place.
+    ///
+    /// The `Buffer` pointer is refreshed once all characters have been appended.
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        self.append(encode_chars(iter));
+    }
+}
+
+impl AsRef<UNICODE_STRING> for OwnedUnicodeString {
+    /// Provides a reference to the internal `UNICODE_STRING`.
+    ///
+    /// This implementation allows for safe access to the underlying `UNICODE_STRING` structure, which
+    /// can be useful for interoperability with Windows APIs that expect a `UNICODE_STRING` pointer.
+    /// The returned reference reflects the current state of the buffer and its lengths.
+    fn as_ref(&self) -> &UNICODE_STRING {
+        &self.unicode_string
+    }
+}
+
+impl From<&mut OwnedUnicodeString> for PCWSTR {
+    /// Converts a mutable reference to an `OwnedUnicodeString` into a `PCWSTR`.
+    ///
+    /// This conversion ensures that the UTF-16 buffer is null-terminated, as required for use
+    /// with many Windows API functions that expect a `PCWSTR` (a pointer to a constant, null-terminated
+    /// UTF-16 string). The conversion does not make a copy of the buffer, maintaining a zero-copy approach.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must remain valid for the lifetime of the `PCWSTR` returned. The caller should
+    /// ensure that the `OwnedUnicodeString` is not mutated in a way that invalidates the pointer.
+    ///
+    /// # Embedded NUL
+    ///
+    /// A `UNICODE_STRING` is counted and may legitimately contain NUL code units, but a `PCWSTR` ends at the
+    /// first NUL. Passing a string with embedded NULs to an API expecting a `PCWSTR` silently truncates it at
+    /// that point. In debug builds this conversion asserts that the string has no embedded NUL; use
+    /// [`OwnedUnicodeString::contains_embedded_nul`] to check beforehand.
+    fn from(value: &mut OwnedUnicodeString) -> Self {
+        debug_assert!(
+            !value.contains_embedded_nul(),
+            "OwnedUnicodeString with an embedded NUL converted to PCWSTR would be truncated"
+        );
+        value.ensure_is_null_terminated();
+        value.buffer.as_ptr()
+    }
+}
+
+impl From<&mut OwnedUnicodeString> for PWSTR {
+    /// Converts a mutable reference to an `OwnedUnicodeString` into a `PWSTR`.
+    ///
+    /// Similar to `Into<PCWSTR>`, this conversion ensures that the UTF-16 buffer is properly null-terminated
+    /// and returns a mutable pointer (`PWSTR`). This is useful for APIs that require a mutable UTF-16 string buffer.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must remain valid and should not be modified in a way that would invalidate the pointer
+    /// while it is being used as a `PWSTR`.
+    ///
+    /// # Embedded NUL
+    ///
+    /// As with `PCWSTR`, C code reading the returned pointer stops at the first NUL. In debug builds this
+    /// conversion asserts that the string has no embedded NUL; use [`OwnedUnicodeString::try_into_pwstr`] to get
+    /// an error instead.
+    fn from(value: &mut OwnedUnicodeString) -> Self {
+        debug_assert!(
+            !value.contains_embedded_nul(),
+            "OwnedUnicodeString with an embedded NUL converted to PWSTR would be truncated"
+        );
+        value.ensure_is_null_terminated();
+        value.buffer.as_mut_ptr()
+    }
+}
+
+impl fmt::Display for OwnedUnicodeString {
+    /// Formats the `OwnedUnicodeString` as a Rust string for display purposes.
+    ///
+    /// This implementation provides a `Display` formatter that allows the `OwnedUnicodeString` to be printed
+    /// directly using Rust's `println!` and other formatting macros. It decodes the UTF-16 buffer to a Rust
+    /// string slice, converting any invalid UTF-16 sequences to the Unicode replacement character (`�`).
+    ///
+    /// The formatter's width, alignment, fill and precision are honored like they are for `str`: strings are
+    /// left-aligned by default, and both the width and the precision are measured in Unicode scalar values, not
+    /// in UTF-16 code units. Padding is written around the decoded characters without building an intermediate
+    /// `String`.
+    ///
+    /// The alternate flag (`{:#}`) switches to a diagnostic form that makes whitespace and control content
+    /// visible: the string is wrapped in guillemets and control characters are escaped as with `char::escape_debug`,
+    /// while all other characters are written unchanged. Width and precision are ignored in this form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("Hello, world!");
+    /// println!("{}", my_string);
+    /// assert_eq!(format!("[{:>7}]", OwnedUnicodeString::from("𐐷ab")), "[    𐐷ab]");
+    /// assert_eq!(format!("[{:*^7.2}]", OwnedUnicodeString::from("abc")), "[**ab***]");
+    /// assert_eq!(format!("{:#}", OwnedUnicodeString::from("Line1\nLine2")), "«Line1\\nLine2»");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&Utf16Display(self.as_slice()), f)
+    }
+}
+
+/// A borrowed UTF-16 slice that can be formatted with `Display`.
+///
+/// This lets any `&[u16]`, such as a buffer filled in by a system call or a field of a C structure, be printed
+/// without copying it into an `OwnedUnicodeString` first. It is formatted exactly like `OwnedUnicodeString`:
+/// unpaired surrogates are shown as the replacement character (`�`), width, alignment, fill and precision are
+/// honored and counted in characters, and the alternate flag (`{:#}`) produces the escaped diagnostic form.
+/// The whole slice is formatted, including any NUL code units.
+///
+/// # Examples
+///
+/// ```rust
+/// use krnlstring::Utf16Display;
+///
+/// let raw = [0x0043u16, 0x003A, 0x005C];
+/// assert_eq!(format!("{}", Utf16Display(&raw)), "C:\\");
+/// assert_eq!(format!("[{:>4}]", Utf16Display(&raw)), "[ C:\\]");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Utf16Display<'a>(pub &'a [u16]);
+
+impl Utf16Display<'_> {
+    fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        decode_utf16(self.0.iter().copied()).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
+
+impl fmt::Display for Utf16Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_char('«')?;
+            for ch in self.chars() {
+                if ch.is_control() {
+                    write!(f, "{}", ch.escape_debug())?;
+                } else {
+                    f.write_char(ch)?;
+                }
+            }
+            return f.write_char('»');
+        }
+
+        let limit = f.precision().unwrap_or(usize::MAX);
+        let (left, right) = match f.width() {
+            Some(width) => {
+                let padding = width.saturating_sub(self.chars().take(limit).count());
+                match f.align() {
+                    Some(fmt::Alignment::Right) => (padding, 0),
+                    Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+                    Some(fmt::Alignment::Left) | None => (0, padding),
+                }
+            }
+            None => (0, 0),
+        };
+
+        let fill = f.fill();
+        for _ in 0..left {
+            f.write_char(fill)?;
+        }
+        for ch in self.chars().take(limit) {
+            f.write_char(ch)?;
+        }
+        for _ in 0..right {
+            f.write_char(fill)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Write for OwnedUnicodeString {
+    /// Appends a string slice to the `OwnedUnicodeString`.
+    ///
+    /// This implementation allows an `OwnedUnicodeString` to be assembled with the `write!` and `writeln!` macros,
+    /// without going through an intermediate `String`. The incoming string slice is encoded as UTF-16 and appended
+    /// in place, refreshing the `Buffer` pointer if the buffer had to grow.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let pid = 4;
+    /// let mut my_string = OwnedUnicodeString::new();
+    /// write!(my_string, "PID {}", pid).unwrap();
+    /// ```
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl Add for OwnedUnicodeString {
+    type Output = OwnedUnicodeString;
+
+    /// Concatenates two `OwnedUnicodeString` instances.
+    ///
+    /// This implementation of the `Add` trait allows for the concatenation of two `OwnedUnicodeString` instances,
+    /// resulting in a new `OwnedUnicodeString` that contains the combined UTF-16 buffers of the operands.
+    /// It ensures that the resulting buffer is properly null-terminated and that the lengths are updated accordingly.
+    ///
+    /// # Safety
+    ///
+    /// The internal buffer is resized to accommodate the concatenated strings, and lengths are recalculated to prevent
+    /// overflows or invalid reads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined length would exceed `u16::MAX` bytes, rather than letting `Length` wrap around.
+    /// Use [`OwnedUnicodeString::try_concat`] to handle this case without panicking.
+    fn add(self, rhs: Self) -> Self::Output {
+        match self.try_concat(&rhs) {
+            Ok(result) => result,
+            Err(error) => panic!("failed to concatenate OwnedUnicodeString: {:?}", error),
+        }
+    }
+}
+
+impl Add<&str> for OwnedUnicodeString {
+    type Output = OwnedUnicodeString;
+
+    /// Concatenates an `OwnedUnicodeString` with a Rust string slice (`&str`).
+    ///
+    /// This implementation allows for concatenating a Rust `&str` directly onto an `OwnedUnicodeString`, returning a new
+    /// `OwnedUnicodeString` with the combined content. The string slice is encoded as UTF-16 before concatenation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined length would exceed `u16::MAX` bytes.
+    fn add(self, rhs: &str) -> Self::Output {
+        let other = OwnedUnicodeString::from(rhs);
+        self + other
+    }
+}
+
+impl Add<char> for OwnedUnicodeString {
+    type Output = OwnedUnicodeString;
+
+    /// Concatenates an `OwnedUnicodeString` with a single character.
+    ///
+    /// The character is encoded as one or two UTF-16 code units and appended in place, which makes it convenient
+    /// for adding a path separator.
+    fn add(mut self, rhs: char) -> Self::Output {
+        self.push(rhs);
+        self
+    }
+}
+
+impl PartialEq for OwnedUnicodeString {
+
+    /// Compares two `OwnedUnicodeString` instances for equality.
+    ///
+    /// This implementation of the `PartialEq` trait allows for the comparison of two `OwnedUnicodeString` instances
+    /// based on the contents of their UTF-16 buffers. It checks if the lengths and contents of both buffers match,
+    /// providing a simple and efficient way to compare Unicode strings.
+    fn eq(&self, other: &Self) -> bool {
+        let self_slice = &self.buffer[..(self.unicode_string.Length / size_of::<u16>() as u16) as usize];
+        let other_slice = &other.buffer[..(other.unicode_string.Length / size_of::<u16>() as u16) as usize];
+        self_slice == other_slice
+    }
+}
+
+impl Eq for OwnedUnicodeString {}
+
+impl PartialOrd for OwnedUnicodeString {
+    /// Compares two `OwnedUnicodeString` instances by their UTF-16 code units.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OwnedUnicodeString {
+    /// Compares two `OwnedUnicodeString` instances by their UTF-16 code units.
+    ///
+    /// Like equality, the ordering only looks at the logical content and ignores trailing NUL terminators.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl PartialEq<str> for OwnedUnicodeString {
+    /// Compares an `OwnedUnicodeString` with a Rust string slice for equality.
+    ///
+    /// The `str` is encoded to UTF-16 on the fly and compared against the logical content, so no temporary
+    /// `OwnedUnicodeString` is allocated.
+    fn eq(&self, other: &str) -> bool {
+        self.as_slice().iter().copied().eq(other.encode_utf16())
+    }
+}
+
+impl PartialEq<&str> for OwnedUnicodeString {
+    /// Compares an `OwnedUnicodeString` with a borrowed Rust string slice for equality.
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialOrd<str> for OwnedUnicodeString {
+    /// Compares an `OwnedUnicodeString` with a Rust string slice by UTF-16 code unit.
+    ///
+    /// The ordering is the same as comparing against `OwnedUnicodeString::from(other)`, but encodes the `str` on
+    /// the fly instead of allocating. Because the comparison is by code unit, characters outside the Basic
+    /// Multilingual Plane sort before U+E000 through U+FFFF, which differs from the byte order of `str`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// assert!(OwnedUnicodeString::from("abc") < *"abd");
+    /// assert!(OwnedUnicodeString::from("abc") > "ab");
+    /// ```
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        Some(self.as_slice().iter().copied().cmp(other.encode_utf16()))
+    }
+}
+
+impl PartialOrd<&str> for OwnedUnicodeString {
+    /// Compares an `OwnedUnicodeString` with a borrowed Rust string slice by UTF-16 code unit.
+    fn partial_cmp(&self, other: &&str) -> Option<Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
+impl Hash for OwnedUnicodeString {
+    /// Hashes the logical content of the `OwnedUnicodeString`, ignoring trailing NUL terminators.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl Borrow<[u16]> for OwnedUnicodeString {
+    /// Borrows the logical content of the `OwnedUnicodeString` as a UTF-16 slice.
+    ///
+    /// This allows collections keyed by `OwnedUnicodeString` to be queried with a plain `&[u16]`. `Borrow`
+    /// requires `Eq`, `Ord` and `Hash` to behave identically on the owned and borrowed forms; all of them operate
+    /// on the same logical slice returned by [`OwnedUnicodeString::as_slice`], so this holds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let mut services = BTreeMap::new();
+    /// services.insert(OwnedUnicodeString::from("Tcpip"), 1);
+    ///
+    /// let key: Vec<u16> = "Tcpip".encode_utf16().collect();
+    /// assert_eq!(services.get(key.as_slice()), Some(&1));
+    /// ```
+    fn borrow(&self) -> &[u16] {
+        self.as_slice()
+    }
+}
+
+impl Index<Range<usize>> for OwnedUnicodeString {
+    type Output = [u16];
+
+    /// Returns the code units of the logical content in `range`.
+    ///
+    /// Indexing operates on the same slice as [`OwnedUnicodeString::as_slice`], so the trailing NUL terminator
+    /// can never be reached. Indices are UTF-16 code units, not characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the logical content, exactly like slice indexing. Use
+    /// [`OwnedUnicodeString::get`] for a non-panicking variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedUnicodeString;
+    ///
+    /// let my_string = OwnedUnicodeString::from("Tcpip");
+    /// assert_eq!(&my_string[0..4], &[0x0054, 0x0063, 0x0070, 0x0069]);
+    /// assert_eq!(my_string[3..].len(), 2);
+    /// ```
+    fn index(&self, range: Range<usize>) -> &[u16] {
+        &self.as_slice()[range]
+    }
+}
+
+impl Index<RangeFrom<usize>> for OwnedUnicodeString {
+    type Output = [u16];
+
+    /// Returns the code units of the logical content from `range.start` to the end.
+    fn index(&self, range: RangeFrom<usize>) -> &[u16] {
+        &self.as_slice()[range]
+    }
+}
+
+impl Index<RangeTo<usize>> for OwnedUnicodeString {
+    type Output = [u16];
+
+    /// Returns the code units of the logical content up to, but excluding, `range.end`.
+    fn index(&self, range: RangeTo<usize>) -> &[u16] {
+        &self.as_slice()[range]
+    }
+}
+
+impl Index<RangeFull> for OwnedUnicodeString {
+    type Output = [u16];
+
+    /// Returns the whole logical content, equivalent to [`OwnedUnicodeString::as_slice`].
+    fn index(&self, _range: RangeFull) -> &[u16] {
+        self.as_slice()
+    }
+}
+
+impl Drop for OwnedUnicodeString {
+    /// Drops the `OwnedUnicodeString`, first zeroing its buffer if it was marked with
+    /// [`OwnedUnicodeString::zeroize_on_drop`].
+    ///
+    /// The entire allocation, including spare capacity, is overwritten with volatile stores before the `Vec<u16>`
+    /// frees it, which costs time linear in the capacity. Strings that are not secret skip this and are dropped
+    /// exactly like the `Vec<u16>` they own.
+    fn drop(&mut self) {
+        self.wipe_if_secret();
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OwnedUnicodeString {
+    /// Serializes the `OwnedUnicodeString` as a UTF-8 string.
+    ///
+    /// The UTF-16 buffer is decoded lossily: invalid sequences, such as lone surrogates, are emitted as the
+    /// Unicode replacement character (`�`), exactly as `Display` renders them.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.chars().collect::<String>())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OwnedUnicodeString {
+    /// Deserializes an `OwnedUnicodeString` from a string by re-encoding it to UTF-16.
+    ///
+    /// Because serialization is lossy, only strings holding valid Unicode round-trip unchanged. A string longer
+    /// than a `UNICODE_STRING` can describe is rejected with a deserialization error rather than a panic, so
+    /// untrusted input cannot abort the caller.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        struct OwnedUnicodeStringVisitor;
+
+        impl serde::de::Visitor<'_> for OwnedUnicodeStringVisitor {
+            type Value = OwnedUnicodeString;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> core::result::Result<Self::Value, E> {
+                OwnedUnicodeString::try_from_str(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(OwnedUnicodeStringVisitor)
+    }
+}
+
+/// A builder that assembles an `OwnedUnicodeString` from many parts with a single final buffer.
+///
+/// Chaining `+` creates a new string for every operand and may reallocate each time. The builder instead collects
+/// the UTF-16 code units in one `Vec<u16>`, which can be sized up front with
+/// [`with_capacity`](UnicodeStringBuilder::with_capacity), and only sets up the `UNICODE_STRING` once
+/// [`build`](UnicodeStringBuilder::build) is called. No `Buffer` pointer exists until then, so none can go stale.
+///
+/// # Examples
+///
+/// ```rust
+/// use krnlstring::{OwnedUnicodeString, UnicodeStringBuilder};
+///
+/// let volume = OwnedUnicodeString::from("HarddiskVolume1");
+/// let mut builder = UnicodeStringBuilder::with_capacity(64);
+/// builder.push_str("\\Device").push_char('\\').push_unicode(&volume);
+/// let path = builder.build();
+/// assert!(path == OwnedUnicodeString::from("\\Device\\HarddiskVolume1"));
+/// ```
+#[derive(Default)]
+pub struct UnicodeStringBuilder {
+    buffer: Vec<u16>,
+}
+
+impl UnicodeStringBuilder {
+    /// Creates an empty builder. Nothing is allocated until the first part is pushed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty builder with room for at least `code_units` code units.
+    ///
+    /// If the final length is known, reserving it here means the whole string is built in one allocation.
+    pub fn with_capacity(code_units: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(code_units),
+        }
+    }
+
+    /// Appends a Rust string slice, encoded as UTF-16.
+    pub fn push_str(&mut self, s: &str) -> &mut Self {
+        self.buffer.extend(s.encode_utf16());
+        self
+    }
+
+    /// Appends the logical content of an `OwnedUnicodeString`, without its NUL terminator.
+    pub fn push_unicode(&mut self, s: &OwnedUnicodeString) -> &mut Self {
+        self.buffer.extend_from_slice(s.as_slice());
+        self
+    }
+
+    /// Appends a single character, encoded as one code unit or a surrogate pair.
+    pub fn push_char(&mut self, c: char) -> &mut Self {
+        let mut units = [0u16; 2];
+        self.buffer.extend_from_slice(c.encode_utf16(&mut units));
+        self
+    }
+
+    /// Returns the number of code units pushed so far.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Turns the collected code units into an `OwnedUnicodeString`, without copying them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the content is longer than a `UNICODE_STRING` can describe. See
+    /// [`try_build`](Self::try_build) for a non-panicking variant.
+    pub fn build(self) -> OwnedUnicodeString {
+        OwnedUnicodeString::from(self.buffer)
+    }
+
+    /// Turns the collected code units into an `OwnedUnicodeString`, returning an error instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::TooLong`] if the content is longer than `u16::MAX` bytes.
+    pub fn try_build(self) -> Result<OwnedUnicodeString> {
+        OwnedUnicodeString::try_from_vec(self.buffer)
+    }
+}
+
+/// A safe wrapper around Windows `STRING` (also known as `ANSI_STRING`) that owns its byte buffer.
+///
+/// `OwnedAnsiString` is the single-byte companion of [`OwnedUnicodeString`]. It follows the same ownership model:
+/// a `Vec<u8>` owns the characters and the embedded `STRING` points into it, with `Length` counting the logical
+/// bytes without trailing NUL terminators and `MaximumLength` the size of the whole buffer. The `Buffer` pointer
+/// is refreshed whenever the vector may have reallocated.
+///
+/// The bytes are stored exactly as given and no code page is applied. Converting from an `OwnedUnicodeString`
+/// keeps ASCII characters and replaces every other character with `?`, see
+/// [`OwnedUnicodeString::to_ansi_lossy`], so only ASCII content round-trips between the two types.
+///
+/// # Safety
+///
+/// As with `OwnedUnicodeString`, the `STRING` contains a mutable pointer into the owned buffer. Code writing
+/// through that pointer must not change the buffer in ways that contradict `Length` and `MaximumLength`.
+pub struct OwnedAnsiString {
+    ansi_string: STRING,
+    buffer: Vec<u8>,
+}
+
+impl OwnedAnsiString {
+    fn compute_size(&mut self) -> Result<()> {
+        self.ansi_string.Buffer = self.buffer.as_mut_ptr();
+
+        let maximum_length = self.buffer.len();
+        let count = self.buffer.iter().rev().take_while(|&&value| value == 0).count();
+        let length = maximum_length - count;
+
+        if maximum_length > MAX_BYTES {
+            return Err(UnicodeStringError::TooLong { bytes: maximum_length });
+        }
+
+        self.ansi_string.Length = length as u16;
+        self.ansi_string.MaximumLength = maximum_length as u16;
+        Ok(())
+    }
+
+    /// Converts a `Vec<u8>` to an `OwnedAnsiString`, returning an error instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnicodeStringError::TooLong`] if the buffer is longer than `u16::MAX` bytes.
+    pub fn try_from_vec(mut value: Vec<u8>) -> Result<OwnedAnsiString> {
+        let ansi_string = STRING {
+            Length: 0,
+            MaximumLength: 0,
+            Buffer: value.as_mut_ptr(),
+        };
+
+        let mut result = Self {
+            ansi_string,
+            buffer: value,
+        };
+
+        result.compute_size()?;
+
+        Ok(result)
+    }
+
+    /// Creates a new, empty `OwnedAnsiString`.
+    ///
+    /// As with [`OwnedUnicodeString::new`], nothing is allocated and both `Length` and `MaximumLength` are zero.
+    pub fn new() -> Self {
+        Self::from(Vec::new())
+    }
+
+    /// Returns the logical content of the string, excluding any trailing NUL terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedAnsiString;
+    ///
+    /// let my_string = OwnedAnsiString::from("Hi");
+    /// assert_eq!(my_string.as_slice(), b"Hi");
+    /// ```
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.ansi_string.Length as usize]
+    }
+
+    /// Returns the length of the string in bytes, which is `Length` and excludes any trailing NUL terminator.
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns `true` if the string has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends raw bytes to the end of the string, in place.
+    ///
+    /// Any trailing NUL terminator is dropped before appending.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting buffer would exceed `u16::MAX` bytes.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.truncate(self.len());
+        self.buffer.extend_from_slice(bytes);
+        if let Err(error) = self.compute_size() {
+            panic!("OwnedAnsiString exceeds the STRING length limit: {:?}", error);
+        }
+    }
+}
+
+impl From<Vec<u8>> for OwnedAnsiString {
+    /// Converts a `Vec<u8>` to an `OwnedAnsiString`, taking ownership of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is longer than `u16::MAX` bytes. See [`OwnedAnsiString::try_from_vec`] for a
+    /// non-panicking variant.
+    fn from(value: Vec<u8>) -> Self {
+        match Self::try_from_vec(value) {
+            Ok(result) => result,
+            Err(error) => panic!("failed to create OwnedAnsiString: {:?}", error),
+        }
+    }
+}
+
+impl Default for OwnedAnsiString {
+    /// Creates an empty `OwnedAnsiString`, equivalent to [`OwnedAnsiString::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for OwnedAnsiString {
+    /// Converts a Rust string slice to an `OwnedAnsiString` by storing its bytes unchanged.
+    ///
+    /// No code page conversion takes place. ASCII text is identical in every ANSI code page; other characters
+    /// are stored as their UTF-8 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::OwnedAnsiString;
+    ///
+    /// let my_string = OwnedAnsiString::from("Hello, world!");
+    /// assert_eq!(my_string.len(), 13);
+    /// ```
+    fn from(value: &str) -> Self {
+        Self::from(Vec::from(value.as_bytes()))
+    }
+}
+
+impl From<&OwnedUnicodeString> for OwnedAnsiString {
+    /// Converts an `OwnedUnicodeString` to an `OwnedAnsiString` using [`OwnedUnicodeString::to_ansi_lossy`].
+    ///
+    /// Every non-ASCII character becomes `?`.
+    fn from(value: &OwnedUnicodeString) -> Self {
+        Self::from(value.to_ansi_lossy())
+    }
+}
+
+impl From<&OwnedAnsiString> for OwnedUnicodeString {
+    /// Converts an `OwnedAnsiString` to an `OwnedUnicodeString`, widening each ASCII byte to one code unit.
+    ///
+    /// Bytes outside the ASCII range have no code-page-independent meaning and are replaced with `?`, so ASCII
+    /// content round-trips through [`OwnedAnsiString`] unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use krnlstring::{OwnedAnsiString, OwnedUnicodeString};
+    ///
+    /// let wide = OwnedUnicodeString::from("Tcpip");
+    /// let ansi = OwnedAnsiString::from(&wide);
+    /// assert!(OwnedUnicodeString::from(&ansi) == wide);
+    /// ```
+    fn from(value: &OwnedAnsiString) -> Self {
+        Self::from(
+            value
+                .as_slice()
+                .iter()
+                .map(|&byte| if byte.is_ascii() { byte as u16 } else { b'?' as u16 })
+                .collect::<Vec<u16>>(),
+        )
+    }
+}
+
+impl AsRef<STRING> for OwnedAnsiString {
+    /// Provides a reference to the internal `STRING`, for passing to APIs that expect a `PCANSI_STRING`.
+    fn as_ref(&self) -> &STRING {
+        &self.ansi_string
+    }
+}
+
+impl fmt::Display for OwnedAnsiString {
+    /// Formats the `OwnedAnsiString` by interpreting its bytes as UTF-8.
+    ///
+    /// ASCII content is shown as is. Byte sequences that are not valid UTF-8 are replaced with the Unicode
+    /// replacement character (`�`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.as_slice().utf8_chunks() {
+            f.write_str(chunk.valid())?;
+            if !chunk.invalid().is_empty() {
+                f.write_str("�")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Add for OwnedAnsiString {
+    type Output = OwnedAnsiString;
+
+    /// Concatenates two `OwnedAnsiString` instances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined length would exceed `u16::MAX` bytes.
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.push_bytes(rhs.as_slice());
+        self
+    }
+}
+
+impl Add<&str> for OwnedAnsiString {
+    type Output = OwnedAnsiString;
+
+    /// Concatenates an `OwnedAnsiString` with the bytes of a Rust string slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined length would exceed `u16::MAX` bytes.
+    fn add(mut self, rhs: &str) -> Self::Output {
+        self.push_bytes(rhs.as_bytes());
+        self
+    }
+}
+
+impl PartialEq for OwnedAnsiString {
+    /// Compares the logical content of two `OwnedAnsiString` instances, ignoring trailing NUL terminators.
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for OwnedAnsiString {}
+
+/// Creates an `OwnedUnicodeString` from a string literal encoded to UTF-16 at compile time.
+///
+/// The literal is encoded into a `&'static [u16]` during constant evaluation, so the only runtime cost is copying
+/// those code units into the owned buffer, not re-encoding the string every time.
+///
+/// # Examples
+///
+/// ```rust
+/// use krnlstring::{krnlstring, OwnedUnicodeString};
+///
+/// fn device_path(volume: &str) -> OwnedUnicodeString {
+///     let mut path = krnlstring!("\\Device\\");
+///     path.push_str(volume);
+///     path
+/// }
+///
+/// assert!(device_path("HarddiskVolume1") == OwnedUnicodeString::from("\\Device\\HarddiskVolume1"));
+/// ```
+#[macro_export]
+macro_rules! krnlstring {
+    ($literal:expr) => {{
+        const LENGTH: usize = $crate::__private::utf16_len($literal);
+        const UTF16: [u16; LENGTH] = $crate::__private::encode_utf16::<LENGTH>($literal);
+        $crate::OwnedUnicodeString::from_wide(&UTF16)
+    }};
+}
+
+#[doc(hidden)]
+pub mod __private {
+    const fn utf8_width(first_byte: u8) -> usize {
+        if first_byte < 0x80 {
+            1
+        } else if first_byte < 0xE0 {
+            2
+        } else if first_byte < 0xF0 {
+            3
+        } else {
+            4
+        }
+    }
+
+    pub const fn utf16_len(s: &str) -> usize {
+        let bytes = s.as_bytes();
+        let mut index = 0;
+        let mut length = 0;
+        while index < bytes.len() {
+            let width = utf8_width(bytes[index]);
+            length += if width == 4 { 2 } else { 1 };
+            index += width;
+        }
+        length
+    }
+
+    pub const fn encode_utf16<const N: usize>(s: &str) -> [u16; N] {
+        let bytes = s.as_bytes();
+        let mut units = [0u16; N];
+        let mut index = 0;
+        let mut unit_index = 0;
+        while index < bytes.len() {
+            let width = utf8_width(bytes[index]);
+            let code_point = match width {
+                1 => bytes[index] as u32,
+                2 => ((bytes[index] as u32 & 0x1F) << 6) | (bytes[index + 1] as u32 & 0x3F),
+                3 => {
+                    ((bytes[index] as u32 & 0x0F) << 12)
+                        | ((bytes[index + 1] as u32 & 0x3F) << 6)
+                        | (bytes[index + 2] as u32 & 0x3F)
+                }
+                _ => {
+                    ((bytes[index] as u32 & 0x07) << 18)
+                        | ((bytes[index + 1] as u32 & 0x3F) << 12)
+                        | ((bytes[index + 2] as u32 & 0x3F) << 6)
+                        | (bytes[index + 3] as u32 & 0x3F)
+                }
+            };
+            if code_point >= 0x10000 {
+                let offset = code_point - 0x10000;
+                units[unit_index] = 0xD800 | (offset >> 10) as u16;
+                units[unit_index + 1] = 0xDC00 | (offset & 0x3FF) as u16;
+                unit_index += 2;
+            } else {
+                units[unit_index] = code_point as u16;
+                unit_index += 1;
+            }
+            index += width;
+        }
+        units
+    }
+}
+
+#[cfg(test)]
+mod test_krnlstring {
+    use alloc::{format, vec};
+    use super::*;
+
+    #[test]
+    fn test_fmt() {
+        let owned_unicode = OwnedUnicodeString::from("Hello, world !");
+        let formated = format!("{}", owned_unicode);
+        assert_eq!(formated,"Hello, world !");
+    }
+
+    #[test]
+    fn test_eq() {
+        let owned_unicode = OwnedUnicodeString::from("Hello, world !");
+        let same = OwnedUnicodeString::from("Hello, world !");
+        let result = owned_unicode == same;
+        assert!(result)
+    }
+
+    #[test]
+    fn test_add() {
+        let owned_unicode = OwnedUnicodeString::from("Hello, world !");
+        let other_str: &str = " Bye";
+        let other = OwnedUnicodeString::from(" !");
+        let expected1 = OwnedUnicodeString::from("Hello, world ! Bye");
+        let expected2 = OwnedUnicodeString::from("Hello, world ! Bye !");
+        let  concat1 =  owned_unicode + other_str;
+        let mut result = concat1 == expected1;
+        assert!(result);
+        let  concat2 =  concat1  + other;
+        result = concat2 == expected2;
+        assert!(result);
+    }
+
+    #[test]
+    fn test_empty_string() {
+        let owned_unicode = OwnedUnicodeString::from("");
+        let expected = OwnedUnicodeString::from(Vec::new());
+        let  result = owned_unicode == expected;
+        assert!(result);
+    }
+
+    #[test]
+    fn test_unicode_characters() {
+        let unicode_str = "こんにちは"; // "Hello" in Japanese
+        let owned_unicode = OwnedUnicodeString::from(unicode_str);
+        let formated = format!("{}", owned_unicode);
+        assert_eq!(formated, unicode_str);
+    }
+
+    #[test]
+    fn test_conversion_to_pcwstr_pwstr() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hello, world!");
+
+        let pcwstr: PCWSTR = (&mut owned_unicode).into();
+        let pwstr: PWSTR = (&mut owned_unicode).into();
+
+        unsafe {
+            assert_eq!(*pcwstr, *pwstr);
+        }
+
+        assert!(owned_unicode.is_null_terminated());
+    }
+
+    #[test]
+    fn test_add_special_characters() {
+        let owned_unicode = OwnedUnicodeString::from("Line1\n");
+        let other = OwnedUnicodeString::from("Line2\tEnd");
+        let expected = OwnedUnicodeString::from("Line1\nLine2\tEnd");
+
+        let result = owned_unicode + other;
+        assert!(result == expected);
+    }
+
+    #[test]
+    fn test_buffer_overflow_protection() {
+        let mut owned_unicode = OwnedUnicodeString::from("Test");
+
+        // Manually extend the buffer to simulate potential overflow
+        owned_unicode.buffer.push(1);
+
+        // Ensure the buffer still respects the max length
+        owned_unicode.compute_size().unwrap();
+        assert!(owned_unicode.unicode_string.Length <= owned_unicode.unicode_string.MaximumLength);
+    }
+
+    #[test]
+    fn test_multiple_consecutive_null_characters() {
+        let mut owned_unicode = OwnedUnicodeString::from("Test");
+
+        // Add multiple null characters
+        owned_unicode.buffer.extend(vec![0, 0, 0]);
+
+        owned_unicode.compute_size().unwrap();
+
+        // Check length is properly adjusted
+        let expected_length = (4 * size_of::<u16>()) as u16;
+        assert_eq!(owned_unicode.unicode_string.Length, expected_length);
+    }
+
+    #[test]
+    fn test_large_input_handling() {
+        let large_string = "A".repeat(10000);
+        let owned_unicode = OwnedUnicodeString::from(large_string.as_str());
+
+        // Check the length is correctly calculated
+        assert_eq!(owned_unicode.unicode_string.Length, (10000 * size_of::<u16>()) as u16);
+    }
+
+    #[test]
+    fn test_equality_case_sensitivity() {
+        let upper_case = OwnedUnicodeString::from("HELLO");
+        let lower_case = OwnedUnicodeString::from("hello");
+
+        assert!(upper_case != lower_case);
+    }
+
+    #[test]
+    fn test_fmt_invalid_utf16_sequence() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hello");
+
+        // Manually add invalid UTF-16 sequence
+        owned_unicode.buffer.push(0xD800); // Half of a surrogate pair
+        owned_unicode.compute_size().unwrap();
+
+        let formated = format!("{}", owned_unicode);
+        assert_eq!(formated, "Hello�");
+    }
+
+    #[test]
+    fn test_ends_with_any() {
+        let owned_unicode = OwnedUnicodeString::from("system.LOG");
+        assert_eq!(owned_unicode.ends_with_any(&[".txt", ".log"]), None);
+        assert_eq!(owned_unicode.ends_with_any_ignore_case(&[".txt", ".log"]), Some(1));
+    }
+
+    #[test]
+    fn test_ends_with_any_no_match() {
+        let owned_unicode = OwnedUnicodeString::from("driver.sys");
+        assert_eq!(owned_unicode.ends_with_any(&[".txt", ".log"]), None);
+        assert_eq!(owned_unicode.ends_with_any_ignore_case(&[".txt", ".log"]), None);
+        assert_eq!(owned_unicode.ends_with_any(&[]), None);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hello");
+        owned_unicode.reserve(64);
+        assert!(owned_unicode.buffer.capacity() >= owned_unicode.buffer.len() + 64);
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+
+        owned_unicode.push_str(", world");
+        owned_unicode.push_str(" !");
+
+        let pwstr: PWSTR = (&mut owned_unicode).into();
+        let length = owned_unicode.as_slice().len();
+        let from_pointer = unsafe { slice::from_raw_parts(pwstr, length + 1) };
+        assert_eq!(&from_pointer[..length], "Hello, world !".encode_utf16().collect::<Vec<u16>>().as_slice());
+        assert_eq!(from_pointer[length], 0);
+        assert_eq!(format!("{}", owned_unicode), "Hello, world !");
+    }
+
+    #[test]
+    fn test_push_str_after_null_termination() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hello");
+        let _: PCWSTR = (&mut owned_unicode).into();
+        owned_unicode.push_str(" Bye");
+        assert!(owned_unicode == "Hello Bye");
+    }
+
+    #[test]
+    fn test_from_wide_with_capacity_pointer_stability() {
+        let prefix: Vec<u16> = "\\Device\\".encode_utf16().collect();
+        let mut owned_unicode = OwnedUnicodeString::from_wide_with_capacity(&prefix, 16);
+        assert!(owned_unicode.buffer.capacity() >= prefix.len() + 16);
+
+        let pointer = owned_unicode.unicode_string.Buffer;
+        owned_unicode.push_str("Harddisk");
+        owned_unicode.push_str("Volume");
+        owned_unicode.push_str("1");
+
+        assert_eq!(owned_unicode.unicode_string.Buffer, pointer);
+        assert_eq!(owned_unicode.buffer.as_mut_ptr(), pointer);
+        assert_eq!(format!("{}", owned_unicode), "\\Device\\HarddiskVolume1");
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut owned_unicode = OwnedUnicodeString::from("A".repeat(1000).as_str());
+        owned_unicode.buffer.truncate(5);
+        owned_unicode.compute_size().unwrap();
+        let capacity_before = owned_unicode.buffer.capacity();
+
+        owned_unicode.shrink_to_fit();
+
+        assert!(owned_unicode.buffer.capacity() < capacity_before);
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, (5 * size_of::<u16>()) as u16);
+        assert_eq!(format!("{}", owned_unicode), "AAAAA");
+    }
+
+    #[test]
+    fn test_repair_surrogates_lone_high_surrogate() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hello");
+        owned_unicode.buffer.push(0xD800);
+        owned_unicode.buffer.push(0x0041);
+        owned_unicode.compute_size().unwrap();
+
+        assert_eq!(owned_unicode.repair_surrogates(), 1);
+        assert!(decode_utf16(owned_unicode.as_slice().iter().copied()).all(|c| c.is_ok()));
+        assert_eq!(format!("{}", owned_unicode), "Hello�A");
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_repair_surrogates_well_formed() {
+        let mut owned_unicode = OwnedUnicodeString::from("a𐐷b");
+        let expected = OwnedUnicodeString::from("a𐐷b");
+
+        assert_eq!(owned_unicode.repair_surrogates(), 0);
+        assert!(owned_unicode == expected);
+    }
+
+    #[test]
+    fn test_fmt_write() {
+        use core::fmt::Write;
+
+        let mut owned_unicode = OwnedUnicodeString::new();
+        let name = "System";
+        write!(owned_unicode, "PID {} ({:#x})", 4, 255).unwrap();
+        writeln!(owned_unicode, " {}", name).unwrap();
+
+        assert_eq!(format!("{}", owned_unicode), "PID 4 (0xff) System\n");
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_new_and_default() {
+        let owned_unicode = OwnedUnicodeString::new();
+        assert_eq!(owned_unicode.unicode_string.Length, 0);
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, 0);
+        assert!(!owned_unicode.unicode_string.Buffer.is_null());
+        assert!(owned_unicode.is_empty());
+        assert!(OwnedUnicodeString::default() == owned_unicode);
+    }
+
+    #[test]
+    fn test_from_iterator_char() {
+        let owned_unicode: OwnedUnicodeString = "a 😀 b".chars().filter(|c| !c.is_whitespace()).collect();
+        assert_eq!(owned_unicode.as_slice(), &[0x0061, 0xD83D, 0xDE00, 0x0062]);
+        assert_eq!(format!("{}", owned_unicode), "a😀b");
+    }
+
+    #[test]
+    fn test_extend_char() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hi");
+        owned_unicode.extend([' ', '😀']);
+        assert!(owned_unicode == "Hi 😀");
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut owned_unicode = OwnedUnicodeString::from("\\Device\\HarddiskVolume1");
+        assert!(owned_unicode.contains("Harddisk"));
+        assert!(owned_unicode.contains("\\Device\\HarddiskVolume1"));
+        assert!(!owned_unicode.contains("Volume2"));
+        assert!(owned_unicode.contains(""));
+
+        let _: PCWSTR = (&mut owned_unicode).into();
+        assert!(!owned_unicode.contains("1\0"));
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let owned_unicode = OwnedUnicodeString::from("\\Device\\Null");
+        assert!(owned_unicode.starts_with("\\Device\\"));
+        assert!(owned_unicode.starts_with("\\Device\\Null"));
+        assert!(!owned_unicode.starts_with("\\Device\\Null\\"));
+        assert!(!owned_unicode.starts_with("\\Registry"));
+        assert!(owned_unicode.starts_with(""));
+    }
+
+    #[test]
+    fn test_ends_with() {
+        let mut owned_unicode = OwnedUnicodeString::from("foo.txt");
+        let _: PCWSTR = (&mut owned_unicode).into();
+        assert!(owned_unicode.ends_with(".txt"));
+        assert!(owned_unicode.ends_with("foo.txt"));
+        assert!(!owned_unicode.ends_with("/foo.txt"));
+        assert!(!owned_unicode.ends_with(".log"));
+        assert!(owned_unicode.ends_with(""));
+    }
+
+    #[test]
+    fn test_find() {
+        let owned_unicode = OwnedUnicodeString::from("Key\\Value");
+        assert_eq!(owned_unicode.find("Key"), Some(0));
+        assert_eq!(owned_unicode.find("\\"), Some(3));
+        assert_eq!(owned_unicode.find("Value"), Some(4));
+        assert_eq!(owned_unicode.find("Data"), None);
+    }
+
+    #[test]
+    fn test_find_counts_code_units() {
+        let owned_unicode = OwnedUnicodeString::from("𐐷\\b");
+        assert_eq!(owned_unicode.find("\\"), Some(2));
+    }
+
+    #[test]
+    fn test_split_at() {
+        let owned_unicode = OwnedUnicodeString::from("Key\\Value");
+        let (key, value) = owned_unicode.split_at(4);
+        assert!(key == "Key\\");
+        assert!(value == "Value");
+        assert_eq!(key.unicode_string.Buffer as *const u16, key.buffer.as_ptr());
+        assert_eq!(value.unicode_string.Buffer as *const u16, value.buffer.as_ptr());
+    }
+
+    #[test]
+    fn test_try_split_at_errors() {
+        let owned_unicode = OwnedUnicodeString::from("a𐐷");
+        assert!(owned_unicode.try_split_at(4).is_err_and(|e| e == UnicodeStringError::IndexOutOfBounds));
+        assert!(owned_unicode.try_split_at(2).is_err_and(|e| e == UnicodeStringError::SplitSurrogate));
+        assert!(owned_unicode.try_split_at(3).is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_at_out_of_range() {
+        OwnedUnicodeString::from("Key").split_at(4);
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut owned_unicode = OwnedUnicodeString::from("  hi  ");
+        owned_unicode.trim();
+        assert!(owned_unicode == "hi");
+        assert!(owned_unicode.is_null_terminated());
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_trim_at_maximum_length() {
+        let mut owned_unicode = OwnedUnicodeString::from(vec![0x41; MAX_CODE_UNITS]);
+        owned_unicode.trim();
+        assert_eq!(owned_unicode.len(), MAX_CODE_UNITS);
+        assert!(owned_unicode.is_null_terminated());
+        assert_eq!(owned_unicode.unicode_string.MaximumLength as usize, MAX_CODE_UNITS * size_of::<u16>());
+        assert!(owned_unicode.debug_check_invariants());
+
+        let mut padded = OwnedUnicodeString::from(vec![0x20; MAX_CODE_UNITS]);
+        padded.trim();
+        assert!(padded.is_empty());
+        assert!(padded.is_null_terminated());
+    }
+
+    #[test]
+    fn test_trim_all_whitespace() {
+        let mut owned_unicode = OwnedUnicodeString::from(" \t\r\n\x0B\x0C ");
+        let trimmed = owned_unicode.trimmed();
+        owned_unicode.trim();
+        assert_eq!(owned_unicode.unicode_string.Length, 0);
+        assert_eq!(trimmed.unicode_string.Length, 0);
+    }
+
+    #[test]
+    fn test_trimmed_leaves_original() {
+        let owned_unicode = OwnedUnicodeString::from("\thi there\n");
+        assert!(owned_unicode.trimmed() == "hi there");
+        assert!(owned_unicode == "\thi there\n");
+    }
+
+    #[test]
+    fn test_make_ascii_uppercase() {
+        let mut owned_unicode = OwnedUnicodeString::from("Path\\Foo");
+        let _: PCWSTR = (&mut owned_unicode).into();
+        let maximum_length = owned_unicode.unicode_string.MaximumLength;
+
+        owned_unicode.make_ascii_uppercase();
+
+        assert!(owned_unicode == "PATH\\FOO");
+        assert!(owned_unicode.is_null_terminated());
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, maximum_length);
+    }
+
+    #[test]
+    fn test_make_ascii_lowercase_non_ascii() {
+        let mut owned_unicode = OwnedUnicodeString::from("CAFé");
+        owned_unicode.make_ascii_lowercase();
+        assert_eq!(format!("{}", owned_unicode), "café");
+
+        owned_unicode.make_ascii_uppercase();
+        assert_eq!(format!("{}", owned_unicode), "CAFé");
+    }
+
+    #[test]
+    fn test_krnlstring_macro() {
+        let owned_unicode = krnlstring!("\\Device\\こんにちは é 😀");
+        assert!(owned_unicode == "\\Device\\こんにちは é 😀");
+
+        let empty = krnlstring!("");
+        assert_eq!(empty.unicode_string.Length, 0);
+    }
+
+    #[test]
+    fn test_add_char() {
+        let owned_unicode = OwnedUnicodeString::from("\\Registry") + '\\' + '😀';
+        assert_eq!(owned_unicode.as_slice().len(), "\\Registry".len() + 1 + 2);
+        assert!(owned_unicode == "\\Registry\\😀");
+    }
+
+    #[test]
+    fn test_push_char() {
+        let mut owned_unicode = OwnedUnicodeString::new();
+        owned_unicode.push('C');
+        owned_unicode.push(':');
+        owned_unicode.push('𐐷');
+        assert_eq!(owned_unicode.as_slice(), &[0x0043, 0x003A, 0xD801, 0xDC37]);
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_chars() {
+        let owned_unicode = OwnedUnicodeString::from("aé𐐷");
+        assert_eq!(owned_unicode.chars().collect::<Vec<char>>(), vec!['a', 'é', '𐐷']);
+        assert!(owned_unicode.try_chars().all(|ch| ch.is_ok()));
+    }
+
+    #[test]
+    fn test_try_chars_invalid() {
+        let owned_unicode = OwnedUnicodeString::from(vec![0x0061, 0xDC00, 0x0062]);
+        assert_eq!(owned_unicode.chars().collect::<Vec<char>>(), vec!['a', '�', 'b']);
+        let errors = owned_unicode.try_chars().filter_map(|ch| ch.err()).collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].unpaired_surrogate(), 0xDC00);
+    }
+
+    #[test]
+    fn test_code_units() {
+        let mut owned_unicode = OwnedUnicodeString::from("Aé𐐷");
+        let _: PCWSTR = (&mut owned_unicode).into();
+        assert_eq!(owned_unicode.code_units().count(), owned_unicode.len());
+        assert_eq!(owned_unicode.code_units().collect::<Vec<u16>>(), vec![0x0041, 0x00E9, 0xD801, 0xDC37]);
+        assert!(!owned_unicode.is_empty());
+        assert!(OwnedUnicodeString::new().is_empty());
+    }
+
+    #[test]
+    fn test_chars_count() {
+        let owned_unicode = OwnedUnicodeString::from("𐐷");
+        assert_eq!(owned_unicode.len(), 2);
+        assert_eq!(owned_unicode.chars_count(), 1);
+
+        let invalid = OwnedUnicodeString::from(vec![0x0061, 0xD800, 0xD800, 0x0062]);
+        assert_eq!(invalid.chars_count(), 4);
+    }
+
+    #[test]
+    fn test_replace_separator() {
+        let owned_unicode = OwnedUnicodeString::from("C:/Users/foo");
+        assert!(owned_unicode.replace("/", "\\") == "C:\\Users\\foo");
+    }
+
+    #[test]
+    fn test_replace_token() {
+        let owned_unicode = OwnedUnicodeString::from("\\Registry\\{ROOT}\\{ROOT}{ROOT");
+        let replaced = owned_unicode.replace("{ROOT}", "Machine");
+        assert!(replaced == "\\Registry\\Machine\\Machine{ROOT");
+    }
+
+    #[test]
+    fn test_replace_edge_cases() {
+        let owned_unicode = OwnedUnicodeString::from("aaa");
+        assert!(owned_unicode.replace("aa", "b") == "ba");
+        assert!(owned_unicode.replace("aaaa", "b") == owned_unicode);
+        assert!(owned_unicode.replace("", "b") == owned_unicode);
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let mut owned_unicode = OwnedUnicodeString::from("Test");
+        owned_unicode.buffer.extend(vec![0, 0]);
+        owned_unicode.compute_size().unwrap();
+        assert_eq!(owned_unicode.into_vec(), "Test".encode_utf16().collect::<Vec<u16>>());
+    }
+
+    #[test]
+    fn test_into_vec_with_nul() {
+        let owned_unicode = OwnedUnicodeString::from("Test");
+        assert_eq!(owned_unicode.into_vec_with_nul(), vec![0x0054, 0x0065, 0x0073, 0x0074, 0]);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_as_bytes() {
+        let mut owned_unicode = OwnedUnicodeString::from("AB");
+        let _: PCWSTR = (&mut owned_unicode).into();
+        assert_eq!(owned_unicode.as_bytes(), &[0x41, 0x00, 0x42, 0x00]);
+        assert_eq!(owned_unicode.as_bytes().len(), owned_unicode.unicode_string.Length as usize);
+    }
+
+    #[test]
+    fn test_from_wide() {
+        let units: Vec<u16> = "Hello".encode_utf16().collect();
+        let owned_unicode = OwnedUnicodeString::from_wide(&units);
+        assert!(owned_unicode == "Hello");
+        assert_eq!(units.len(), 5);
+    }
+
+    #[test]
+    fn test_from_wide_empty() {
+        let owned_unicode = OwnedUnicodeString::from_wide(&[]);
+        assert!(owned_unicode.is_empty());
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, 0);
+    }
+
+    #[test]
+    fn test_from_wide_embedded_nul() {
+        let owned_unicode = OwnedUnicodeString::from_wide(&[0x0061, 0, 0x0062, 0, 0]);
+        assert_eq!(owned_unicode.as_slice(), &[0x0061, 0, 0x0062]);
+        assert_eq!(owned_unicode.unicode_string.Length, (3 * size_of::<u16>()) as u16);
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, (5 * size_of::<u16>()) as u16);
+    }
+
+    #[test]
+    fn test_contains_embedded_nul() {
+        let owned_unicode = OwnedUnicodeString::from("a\0b");
+        assert_eq!(owned_unicode.unicode_string.Length, (3 * size_of::<u16>()) as u16);
+        assert!(owned_unicode.contains_embedded_nul());
+
+        let terminated = OwnedUnicodeString::from("ab\0\0");
+        assert!(!terminated.contains_embedded_nul());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_conversion_to_pcwstr_embedded_nul() {
+        let mut owned_unicode = OwnedUnicodeString::from("a\0b");
+        let _: PCWSTR = (&mut owned_unicode).into();
+    }
+
+    #[test]
+    fn test_from_pcwstr() {
+        let mut wide: Vec<u16> = "\\Device\\Null".encode_utf16().collect();
+        wide.push(0);
+        wide.extend("garbage".encode_utf16());
+
+        let owned_unicode = unsafe { OwnedUnicodeString::from_pcwstr(wide.as_ptr()) };
+        assert!(owned_unicode == "\\Device\\Null");
+        assert_ne!(owned_unicode.buffer.as_ptr(), wide.as_ptr());
+    }
+
+    #[test]
+    fn test_from_pcwstr_null_and_capped() {
+        let empty = unsafe { OwnedUnicodeString::from_pcwstr(core::ptr::null()) };
+        assert!(empty.is_empty());
+
+        let unterminated = vec![0x0041u16; 40000];
+        let capped = unsafe { OwnedUnicodeString::from_pcwstr(unterminated.as_ptr()) };
+        assert_eq!(capped.len(), MAX_CODE_UNITS);
+    }
+
+    #[test]
+    fn test_eq_raw() {
+        let owned_unicode = OwnedUnicodeString::from("\\Device\\Null");
+        let mut local: Vec<u16> = "\\Device\\Null".encode_utf16().collect();
+        let raw = UNICODE_STRING {
+            Length: (local.len() * size_of::<u16>()) as u16,
+            MaximumLength: (local.len() * size_of::<u16>()) as u16,
+            Buffer: local.as_mut_ptr(),
+        };
+        assert!(unsafe { owned_unicode.eq_raw(&raw) });
+
+        let shorter = UNICODE_STRING { Length: raw.Length - 2, ..raw };
+        assert!(!unsafe { owned_unicode.eq_raw(&shorter) });
+
+        let empty = UNICODE_STRING { Length: 0, MaximumLength: 0, Buffer: core::ptr::null_mut() };
+        assert!(unsafe { OwnedUnicodeString::new().eq_raw(&empty) });
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip() {
+        let owned_unicode = OwnedUnicodeString::from("こんにちは");
+        let json = serde_json::to_string(&owned_unicode).unwrap();
+        assert_eq!(json, "\"こんにちは\"");
+
+        let deserialized: OwnedUnicodeString = serde_json::from_str(&json).unwrap();
+        assert!(deserialized == owned_unicode);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_invalid_utf16() {
+        let owned_unicode = OwnedUnicodeString::from(vec![0x0041, 0xD800]);
+        assert_eq!(serde_json::to_string(&owned_unicode).unwrap(), "\"A�\"");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_too_long() {
+        let json = format!("\"{}\"", "A".repeat(40000));
+        let error = serde_json::from_str::<OwnedUnicodeString>(&json).err().unwrap();
+        assert!(error.to_string().contains("string of 80000 bytes exceeds the UNICODE_STRING limit"));
+
+        let json = format!("\"{}\"", "A".repeat(MAX_CODE_UNITS));
+        let deserialized: OwnedUnicodeString = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.len(), MAX_CODE_UNITS);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut owned_unicode = OwnedUnicodeString::from("a1b2c3");
+        owned_unicode.retain(|c| c.is_ascii_digit());
+        assert!(owned_unicode == "123");
+        assert_eq!(owned_unicode.buffer.len(), 3);
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_retain_surrogates() {
+        let mut owned_unicode = OwnedUnicodeString::from(vec![0x0061, 0xD801, 0xDC37, 0xD800, 0x0009]);
+        owned_unicode.retain(|c| !c.is_control());
+        assert_eq!(owned_unicode.as_slice(), &[0x0061, 0xD801, 0xDC37, 0xFFFD]);
+
+        owned_unicode.retain(|c| c != char::REPLACEMENT_CHARACTER);
+        assert!(owned_unicode == "a𐐷");
+    }
+
+    #[test]
+    fn test_split() {
+        let owned_unicode = OwnedUnicodeString::from("Registry\\Machine\\SYSTEM");
+        let segments = owned_unicode.split('\\').collect::<Vec<OwnedUnicodeString>>();
+        assert_eq!(segments.len(), 3);
+        assert!(segments[0] == "Registry");
+        assert!(segments[1] == "Machine");
+        assert!(segments[2] == "SYSTEM");
+    }
+
+    #[test]
+    fn test_split_empty_segments() {
+        let owned_unicode = OwnedUnicodeString::from("\\a\\\\b\\");
+        let segments = owned_unicode.split('\\').map(|segment| format!("{}", segment)).collect::<Vec<_>>();
+        assert_eq!(segments, vec!["", "a", "", "b", ""]);
+
+        assert_eq!(OwnedUnicodeString::new().split('\\').count(), 1);
+    }
+
+    #[test]
+    fn test_split_astral_delimiter() {
+        let owned_unicode = OwnedUnicodeString::from("a😀b😀");
+        let segments = owned_unicode.split('😀').map(|segment| format!("{}", segment)).collect::<Vec<_>>();
+        assert_eq!(segments, vec!["a", "b", ""]);
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        let owned_unicode = OwnedUnicodeString::from("\\??\\C:\\file");
+        let stripped = owned_unicode.strip_prefix("\\??\\").unwrap();
+        assert!(stripped == "C:\\file");
+        assert_eq!(stripped.unicode_string.Buffer as *const u16, stripped.buffer.as_ptr());
+
+        assert!(owned_unicode.strip_prefix("\\\\?\\").is_none());
+        assert!(owned_unicode.strip_prefix("\\??\\C:\\file").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_strip_suffix() {
+        let owned_unicode = OwnedUnicodeString::from("driver.sys");
+        assert!(owned_unicode.strip_suffix(".sys").unwrap() == "driver");
+        assert!(owned_unicode.strip_suffix(".dll").is_none());
+        assert!(owned_unicode.strip_suffix("driver.sys").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut owned_unicode = OwnedUnicodeString::from("a𐐷b");
+        owned_unicode.reverse();
+        assert!(owned_unicode == "b𐐷a");
+        assert!(owned_unicode.try_chars().all(|ch| ch.is_ok()));
+    }
+
+    #[test]
+    fn test_reverse_lone_surrogates() {
+        let mut owned_unicode = OwnedUnicodeString::from(vec![0xDC00, 0xD800, 0x0061]);
+        owned_unicode.reverse();
+        assert_eq!(owned_unicode.as_slice(), &[0x0061, 0xFFFD, 0xFFFD]);
+    }
+
+    #[test]
+    fn test_insert_str() {
+        let mut owned_unicode = OwnedUnicodeString::from("Machine");
+        owned_unicode.insert_str(0, "\\Registry\\");
+        assert!(owned_unicode == "\\Registry\\Machine");
+
+        owned_unicode.insert_str(10, "<>");
+        assert!(owned_unicode == "\\Registry\\<>Machine");
+
+        let _: PCWSTR = (&mut owned_unicode).into();
+        let length = owned_unicode.len();
+        owned_unicode.insert_str(length, "\\SYSTEM");
+        assert!(owned_unicode == "\\Registry\\<>Machine\\SYSTEM");
+        assert!(owned_unicode.is_null_terminated());
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    fn test_try_insert_str_errors() {
+        let mut owned_unicode = OwnedUnicodeString::from("a𐐷");
+        assert_eq!(owned_unicode.try_insert_str(4, "b"), Err(UnicodeStringError::IndexOutOfBounds));
+        assert_eq!(owned_unicode.try_insert_str(2, "b"), Err(UnicodeStringError::SplitSurrogate));
+        assert!(owned_unicode == "a𐐷");
+    }
+
+    #[test]
+    fn test_remove_range() {
+        let mut owned_unicode = OwnedUnicodeString::from("abcdef");
+        owned_unicode.remove_range(1, 4);
+        assert!(owned_unicode == "aef");
+
+        owned_unicode.remove_range(0, 0);
+        assert!(owned_unicode == "aef");
+
+        let mut astral = OwnedUnicodeString::from("a𐐷");
+        astral.remove_range(2, 3);
+        assert_eq!(format!("{}", astral), "a�");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remove_range_out_of_range() {
+        let mut owned_unicode = OwnedUnicodeString::from("abc");
+        let _: PCWSTR = (&mut owned_unicode).into();
+        owned_unicode.remove_range(1, 4);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let owned_unicode = OwnedUnicodeString::from("ab");
+        assert!(owned_unicode.repeat(0).is_empty());
+        assert!(owned_unicode.repeat(1) == owned_unicode);
+        assert!(owned_unicode.repeat(3) == "ababab");
+    }
+
+    #[test]
+    fn test_try_repeat_overflow() {
+        let owned_unicode = OwnedUnicodeString::from("A".repeat(10000).as_str());
+        assert!(owned_unicode.try_repeat(3).is_ok());
+        assert!(owned_unicode
+            .try_repeat(4)
+            .is_err_and(|error| error == UnicodeStringError::TooLong { bytes: 80000 }));
+        assert!(owned_unicode.try_repeat(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_to_multi_sz() {
+        let strings = [
+            OwnedUnicodeString::from("Tcpip"),
+            OwnedUnicodeString::from("Afd"),
+            OwnedUnicodeString::from("こんにちは"),
+        ];
+        let multi_sz = OwnedUnicodeString::to_multi_sz(&strings);
+        assert_eq!(&multi_sz[multi_sz.len() - 2..], &[0, 0]);
+
+        let decoded = multi_sz[..multi_sz.len() - 2]
+            .split(|&unit| unit == 0)
+            .map(OwnedUnicodeString::from_wide)
+            .collect::<Vec<OwnedUnicodeString>>();
+        assert_eq!(decoded.len(), strings.len());
+        assert!(decoded.iter().zip(strings.iter()).all(|(a, b)| a == b));
+    }
+
+    #[test]
+    fn test_to_multi_sz_empty() {
+        assert_eq!(OwnedUnicodeString::to_multi_sz(&[]), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_from_multi_sz() {
+        let strings = [OwnedUnicodeString::from("Tcpip"), OwnedUnicodeString::from("Afd")];
+        let parsed = OwnedUnicodeString::from_multi_sz(&OwnedUnicodeString::to_multi_sz(&strings));
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[0] == strings[0]);
+        assert!(parsed[1] == strings[1]);
+
+        let unterminated: Vec<u16> = "Tcpip\0Afd".encode_utf16().collect();
+        let parsed = OwnedUnicodeString::from_multi_sz(&unterminated);
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[1] == strings[1]);
+    }
+
+    #[test]
+    fn test_from_multi_sz_empty() {
+        assert!(OwnedUnicodeString::from_multi_sz(&[]).is_empty());
+        assert!(OwnedUnicodeString::from_multi_sz(&[0, 0]).is_empty());
+    }
+
+    #[test]
+    fn test_borrow_map_lookup() {
+        use alloc::collections::BTreeMap;
+        use std::collections::HashMap;
+
+        let mut tree = BTreeMap::new();
+        let mut hash = HashMap::new();
+        let mut key = OwnedUnicodeString::from("Tcpip");
+        let _: PCWSTR = (&mut key).into();
+        tree.insert(key, 1);
+        hash.insert(OwnedUnicodeString::from("Afd"), 2);
+
+        let tcpip: Vec<u16> = "Tcpip".encode_utf16().collect();
+        let afd: Vec<u16> = "Afd".encode_utf16().collect();
+        assert_eq!(tree.get(tcpip.as_slice()), Some(&1));
+        assert_eq!(hash.get(afd.as_slice()), Some(&2));
+        assert_eq!(tree.get(afd.as_slice()), None);
+    }
+
+    #[test]
+    fn test_ord() {
+        let (ab, abc, abd) = (OwnedUnicodeString::from("ab"), OwnedUnicodeString::from("abc"), OwnedUnicodeString::from("abd"));
+        assert!(abc < abd);
+        assert!(ab < abc);
+        assert_eq!(
+            OwnedUnicodeString::from("abc").cmp(&OwnedUnicodeString::from("abc\0")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_as_null_terminated_slice() {
+        let mut owned_unicode = OwnedUnicodeString::from("Test");
+        let slice = owned_unicode.as_null_terminated_slice();
+        assert_eq!(slice.len(), 5);
+        assert_eq!(slice.last(), Some(&0));
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+
+        let mut padded = OwnedUnicodeString::from("Test\0\0\0");
+        assert_eq!(padded.as_null_terminated_slice().len(), 5);
+    }
+
+    #[test]
+    fn test_try_into_pcwstr() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hello");
+        let pcwstr = owned_unicode.try_into_pcwstr().unwrap();
+        assert_eq!(pcwstr, owned_unicode.buffer.as_ptr());
+        assert!(owned_unicode.is_null_terminated());
+    }
+
+    #[test]
+    fn test_try_into_pcwstr_embedded_nul() {
+        let mut owned_unicode = OwnedUnicodeString::from("ab\0cd");
+        assert_eq!(owned_unicode.try_into_pcwstr(), Err(UnicodeStringError::EmbeddedNul { index: 2 }));
+        assert_eq!(owned_unicode.try_into_pwstr(), Err(UnicodeStringError::EmbeddedNul { index: 2 }));
+        assert!(!owned_unicode.is_null_terminated());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_conversion_to_pwstr_embedded_nul() {
+        let mut owned_unicode = OwnedUnicodeString::from("a\0b");
+        let _: PWSTR = (&mut owned_unicode).into();
+    }
+
+    #[test]
+    fn test_compute_size_overflow() {
+        let large: Vec<u16> = "A".repeat(40000).encode_utf16().collect();
+        let error = OwnedUnicodeString::try_from_vec(large).err();
+        assert_eq!(error, Some(UnicodeStringError::TooLong { bytes: 80000 }));
+
+        let mut owned_unicode = OwnedUnicodeString::from("A".repeat(30000).as_str());
+        owned_unicode.buffer.extend(vec![0x0041; 10000]);
+        assert_eq!(owned_unicode.compute_size(), Err(UnicodeStringError::TooLong { bytes: 80000 }));
+        assert_eq!(owned_unicode.unicode_string.Buffer, owned_unicode.buffer.as_mut_ptr());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_overflow_panics() {
+        let _ = OwnedUnicodeString::from("A".repeat(40000).as_str());
+    }
+
+    #[test]
+    fn test_try_insert_str_overflow() {
+        let mut owned_unicode = OwnedUnicodeString::from("A".repeat(30000).as_str());
+        let error = owned_unicode.try_insert_str(0, &"B".repeat(10000));
+        assert_eq!(error, Err(UnicodeStringError::TooLong { bytes: 80000 }));
+        assert_eq!(owned_unicode.len(), 30000);
+    }
+
+    #[test]
+    fn test_null_termination_at_maximum_length() {
+        let mut owned_unicode = OwnedUnicodeString::from("A".repeat(32767).as_str());
+        let _: PCWSTR = (&mut owned_unicode).into();
+        assert_eq!(owned_unicode.unicode_string.Length, u16::MAX - 1);
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, u16::MAX - 1);
+
+        owned_unicode.repair_surrogates();
+        owned_unicode.replace_invalid('?');
+        assert_eq!(owned_unicode.try_insert_str(0, ""), Ok(()));
+        assert!(owned_unicode.is_null_terminated());
+        assert_eq!(owned_unicode.unicode_string.Length, u16::MAX - 1);
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, u16::MAX - 1);
+        assert!(owned_unicode.debug_check_invariants());
+
+        assert_eq!(
+            owned_unicode.try_insert_str(0, "B"),
+            Err(UnicodeStringError::TooLong { bytes: 65536 })
+        );
+        assert_eq!(owned_unicode.len(), 32767);
+    }
+
+    #[test]
+    fn test_try_concat() {
+        let owned_unicode = OwnedUnicodeString::from("Hello, ");
+        let result = owned_unicode.try_concat(&OwnedUnicodeString::from("world")).unwrap();
+        assert!(result == "Hello, world");
+    }
+
+    #[test]
+    fn test_try_concat_overflow() {
+        let first = OwnedUnicodeString::from("A".repeat(20000).as_str());
+        let second = OwnedUnicodeString::from("B".repeat(20000).as_str());
+        let error = first.try_concat(&second).err();
+        assert_eq!(error, Some(UnicodeStringError::TooLong { bytes: 80000 }));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_overflow_panics() {
+        let first = OwnedUnicodeString::from("A".repeat(20000).as_str());
+        let second = OwnedUnicodeString::from("B".repeat(20000).as_str());
+        let _ = first + second;
+    }
+
+    #[test]
+    fn test_error_display() {
+        use alloc::string::ToString;
+
+        assert_eq!(
+            UnicodeStringError::TooLong { bytes: 80000 }.to_string(),
+            "string of 80000 bytes exceeds the UNICODE_STRING limit of 65535 bytes"
+        );
+        assert_eq!(
+            UnicodeStringError::InvalidUtf16 { index: 3 }.to_string(),
+            "invalid UTF-16 code unit at index 3"
+        );
+        assert_eq!(
+            UnicodeStringError::EmbeddedNul { index: 1 }.to_string(),
+            "embedded NUL code unit at index 1"
+        );
+        assert_eq!(
+            UnicodeStringError::InvalidUtf8 { index: 2 }.to_string(),
+            "invalid UTF-8 sequence at byte offset 2"
+        );
+        assert_eq!(UnicodeStringError::IndexOutOfBounds.to_string(), "code-unit index out of bounds");
+        assert_eq!(UnicodeStringError::SplitSurrogate.to_string(), "code-unit index splits a surrogate pair");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_into_boxed_error() {
+        fn fails() -> core::result::Result<(), std::boxed::Box<dyn std::error::Error>> {
+            OwnedUnicodeString::try_from_vec(alloc::vec![0x41; 40000])?;
+            Ok(())
+        }
+
+        let err = fails().unwrap_err();
+        assert_eq!(err.to_string(), "string of 80000 bytes exceeds the UNICODE_STRING limit of 65535 bytes");
+    }
+
+    #[test]
+    fn test_to_ansi_lossy() {
+        assert_eq!(OwnedUnicodeString::from("abc").to_ansi_lossy(), b"abc");
+        assert_eq!(OwnedUnicodeString::from("a€b").to_ansi_lossy(), b"a?b");
+        assert_eq!(OwnedUnicodeString::from("𐐷").to_ansi_lossy(), b"?");
+        assert_eq!(OwnedUnicodeString::from_wide(&[0x41, 0xD800]).to_ansi_lossy(), b"A?");
+        assert!(OwnedUnicodeString::new().to_ansi_lossy().is_empty());
+    }
+
+    #[test]
+    fn test_owned_ansi_string() {
+        let hello = OwnedAnsiString::from("Hello, ");
+        assert_eq!(hello.len(), 7);
+        assert_eq!(hello.as_ref().Length, 7);
+        assert_eq!(hello.as_ref().Buffer as *const u8, hello.as_slice().as_ptr());
+
+        let greeting = hello + OwnedAnsiString::from("world") + "!";
+        assert_eq!(greeting.as_slice(), b"Hello, world!");
+        assert_eq!(format!("{}", greeting), "Hello, world!");
+        assert_eq!(greeting.as_ref().Buffer as *const u8, greeting.as_slice().as_ptr());
+
+        let terminated = OwnedAnsiString::from(alloc::vec![b'a', b'b', 0]);
+        assert_eq!(terminated.len(), 2);
+        assert_eq!(terminated.as_ref().MaximumLength, 3);
+        assert!(terminated == OwnedAnsiString::from("ab"));
+
+        assert!(OwnedAnsiString::new().is_empty());
+        assert_eq!(format!("{}", OwnedAnsiString::from(alloc::vec![b'A', 0xFF])), "A�");
+    }
+
+    #[test]
+    fn test_ansi_unicode_round_trip() {
+        let wide = OwnedUnicodeString::from(r"\Device\Null");
+        let ansi = OwnedAnsiString::from(&wide);
+        assert_eq!(ansi.as_slice(), br"\Device\Null");
+        assert!(OwnedUnicodeString::from(&ansi) == wide);
+
+        let lossy = OwnedAnsiString::from(&OwnedUnicodeString::from("Grüße"));
+        assert_eq!(lossy.as_slice(), b"Gr??e");
+        assert!(OwnedUnicodeString::from(&OwnedAnsiString::from("é")) == "??");
+        assert!(OwnedAnsiString::try_from_vec(alloc::vec![b'a'; 70000]).is_err());
+    }
+
+    #[test]
+    fn test_get() {
+        let mut my_string = OwnedUnicodeString::from("Hello");
+        assert_eq!(my_string.get(1..3), Some(&[0x65, 0x6C][..]));
+        assert_eq!(my_string.get(0..5).map(<[u16]>::len), Some(5));
+        assert_eq!(my_string.get(2..2), Some(&[][..]));
+        assert_eq!(my_string.get(5..5), Some(&[][..]));
+
+        assert_eq!(my_string.get(4..6), None);
+        assert_eq!(my_string.get(6..6), None);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = my_string.get(3..1);
+        assert_eq!(reversed, None);
+
+        // The NUL terminator is not part of the logical content.
+        my_string.as_null_terminated_slice();
+        assert_eq!(my_string.get(0..6), None);
+
+        my_string.get_mut(0..1).unwrap()[0] = 0x4A;
+        assert!(my_string == "Jello");
+        assert!(my_string.get_mut(3..9).is_none());
+    }
+
+    #[test]
+    fn test_index_ranges() {
+        let mut my_string = OwnedUnicodeString::from("abcd");
+        my_string.as_null_terminated_slice();
+
+        assert_eq!(&my_string[1..3], &[0x62, 0x63]);
+        assert_eq!(&my_string[2..], &[0x63, 0x64]);
+        assert_eq!(&my_string[..1], &[0x61]);
+        assert_eq!(&my_string[..], my_string.as_slice());
+        assert_eq!(my_string[4..].len(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_excludes_terminator() {
+        let mut my_string = OwnedUnicodeString::from("abcd");
+        my_string.as_null_terminated_slice();
+        let _ = &my_string[0..5];
+    }
+
+    #[test]
+    fn test_debug_check_invariants() {
+        let mut my_string = OwnedUnicodeString::from("Hello");
+        assert!(my_string.debug_check_invariants());
+        my_string.push_str(", world");
+        my_string.as_null_terminated_slice();
+        my_string.shrink_to_fit();
+        assert!(my_string.debug_check_invariants());
+
+        let original = my_string.unicode_string.Buffer;
+        my_string.unicode_string.Buffer = core::ptr::null_mut();
+        assert!(!my_string.debug_check_invariants());
+        my_string.unicode_string.Buffer = original;
+
+        my_string.unicode_string.Length = my_string.unicode_string.MaximumLength + 2;
+        assert!(!my_string.debug_check_invariants());
+        my_string.unicode_string.Length = 3;
+        assert!(!my_string.debug_check_invariants());
+
+        my_string.compute_size().unwrap();
+        assert!(my_string.debug_check_invariants());
+        my_string.unicode_string.MaximumLength = (my_string.buffer.len() * 2 + 2) as u16;
+        assert!(!my_string.debug_check_invariants());
+    }
+
+    #[test]
+    fn test_parse_env_block() {
+        let block: Vec<u16> = "=C:=C:\\Users\0Path=C:\\Windows;C:\\Tools\0OPTS=a=b\0NOVALUE\0EMPTY=\0\0IGNORED=1\0"
+            .encode_utf16()
+            .collect();
+        let variables = OwnedUnicodeString::parse_env_block(&block);
+        let pairs: Vec<(alloc::string::String, alloc::string::String)> = variables
+            .iter()
+            .map(|(name, value)| (format!("{}", name), format!("{}", value)))
+            .collect();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("=C:".into(), "C:\\Users".into()),
+                ("Path".into(), "C:\\Windows;C:\\Tools".into()),
+                ("OPTS".into(), "a=b".into()),
+                ("NOVALUE".into(), "".into()),
+                ("EMPTY".into(), "".into()),
+            ]
+        );
+
+        assert!(OwnedUnicodeString::parse_env_block(&[]).is_empty());
+        assert!(OwnedUnicodeString::parse_env_block(&[0, 0]).is_empty());
+        let single = OwnedUnicodeString::parse_env_block(&[0x3D]);
+        assert!(single[0].0 == "=" && single[0].1.is_empty());
+    }
+
+    #[test]
+    fn test_zeroize_on_drop() {
+        let mut secret = OwnedUnicodeString::new_secret("hunter2");
+        assert!(secret.is_secret());
+        secret.as_null_terminated_slice();
+
+        // Run the same wiping step `Drop` performs, while the buffer is still observable.
+        secret.wipe_if_secret();
+        assert_eq!(secret.buffer.len(), 8);
+        assert!(secret.buffer.iter().all(|&unit| unit == 0));
+
+        let mut public = OwnedUnicodeString::from("hello");
+        assert!(!public.is_secret());
+        public.wipe_if_secret();
+        assert!(public == "hello");
+
+        let mut marked = OwnedUnicodeString::from("key");
+        marked.zeroize_on_drop();
+        assert!(marked.is_secret());
+        assert_eq!(marked.into_vec(), vec![0x6B, 0x65, 0x79]);
+    }
+
+    #[test]
+    fn test_secret_growth_wipes_old_allocation() {
+        let mut secret = OwnedUnicodeString::new_secret("pw");
+        secret.shrink_to_fit();
+        let capacity = secret.buffer.capacity();
+
+        secret.push_str("-and-more-secret-content");
+        assert!(secret.buffer.capacity() > capacity);
+        assert!(secret == "pw-and-more-secret-content");
+        assert!(secret.debug_check_invariants());
+
+        secret.insert_str(0, "my ");
+        secret.reserve(64);
+        assert!(secret.buffer.capacity() >= secret.buffer.len() + 64);
+        secret.reverse();
+        secret.reverse();
+        secret.as_null_terminated_slice();
+        secret.shrink_to_fit();
+        assert_eq!(secret.buffer.capacity(), secret.buffer.len());
+        assert!(secret == "my pw-and-more-secret-content");
+        assert!(secret.debug_check_invariants());
+
+        let mut removed = OwnedUnicodeString::new_secret("abcdef");
+        removed.remove_range(2, 6);
+        removed.wipe_if_secret();
+        // SAFETY: the allocation is still owned by `removed.buffer` and was just overwritten.
+        let allocation = unsafe { slice::from_raw_parts(removed.buffer.as_ptr(), removed.buffer.capacity()) };
+        assert!(allocation.iter().all(|&unit| unit == 0));
+    }
+
+    #[test]
+    fn test_try_from_slice_validates_utf16() {
+        let valid: Vec<u16> = "h€llo 𐐷".encode_utf16().collect();
+        let my_string = OwnedUnicodeString::try_from(valid.as_slice()).unwrap();
+        assert_eq!(my_string.as_slice(), valid.as_slice());
+
+        let terminated = OwnedUnicodeString::try_from(&[0x61, 0x62, 0][..]).unwrap();
+        assert_eq!(terminated.len(), 2);
+
+        assert_eq!(
+            OwnedUnicodeString::try_from(&[0x61, 0x62, 0xD800][..]).err(),
+            Some(UnicodeStringError::InvalidUtf16 { index: 2 })
+        );
+        assert_eq!(
+            OwnedUnicodeString::try_from(&[0xD801, 0xDC37, 0xD800, 0x61][..]).err(),
+            Some(UnicodeStringError::InvalidUtf16 { index: 2 })
+        );
+        assert_eq!(
+            OwnedUnicodeString::try_from(&[0x61, 0xDC00, 0x62][..]).err(),
+            Some(UnicodeStringError::InvalidUtf16 { index: 1 })
+        );
+        assert!(OwnedUnicodeString::try_from(&[0u16; 0][..]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_length_queries() {
+        let mut my_string = OwnedUnicodeString::with_capacity(16);
+        assert_eq!(my_string.length(), 0);
+        assert_eq!(my_string.maximum_length(), 0);
+        assert!(my_string.capacity_code_units() >= 16);
+        let buffer = my_string.as_ref().Buffer;
+
+        my_string.push_str("Hello");
+        assert_eq!(my_string.length(), 10);
+        assert_eq!(my_string.maximum_length(), 10);
+        assert_eq!(my_string.as_ref().Buffer, buffer);
+
+        my_string.as_null_terminated_slice();
+        assert_eq!(my_string.length(), 10);
+        assert_eq!(my_string.maximum_length(), 12);
+        assert!(my_string.capacity_code_units() * 2 >= my_string.maximum_length() as usize);
+        assert_eq!(my_string.length() as usize, my_string.len() * 2);
+    }
+
+    #[test]
+    fn test_trim_nul() {
+        let mut owned_unicode = OwnedUnicodeString::from("Test\0\0\0");
+        assert_eq!(owned_unicode.buffer.len(), 7);
+        assert_eq!(owned_unicode.unicode_string.Length, 8);
+
+        owned_unicode.trim_nul();
+        assert_eq!(owned_unicode.buffer.len(), 4);
+        assert_eq!(owned_unicode.unicode_string.Length, 8);
+        assert_eq!(owned_unicode.unicode_string.MaximumLength, 8);
+        assert!(owned_unicode == "Test");
+        assert!(owned_unicode.debug_check_invariants());
+
+        // Embedded NULs are content and are kept.
+        let mut embedded = OwnedUnicodeString::from_wide(&[0x61, 0, 0x62, 0]);
+        embedded.trim_nul();
+        assert_eq!(embedded.buffer, vec![0x61, 0, 0x62]);
+
+        let mut empty = OwnedUnicodeString::from("\0\0");
+        empty.trim_nul();
+        assert!(empty.is_empty() && empty.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_to_unicode_string() {
+        fn read_by_value(value: UNICODE_STRING) -> Vec<u16> {
+            // SAFETY: the caller keeps the owning string alive and unmodified for this call.
+            unsafe { slice::from_raw_parts(value.Buffer, value.Length as usize / 2) }.to_vec()
+        }
+
+        let mut my_string = OwnedUnicodeString::from("Kernel");
+        my_string.as_null_terminated_slice();
+        let value = my_string.to_unicode_string();
+        assert_eq!(value.Buffer, my_string.as_ref().Buffer);
+        assert_eq!(value.Length, 12);
+        assert_eq!(value.MaximumLength, 14);
+        assert_eq!(read_by_value(value), my_string.as_slice());
+    }
 
     #[test]
-    fn test_fmt() {
-        let owned_unicode = OwnedUnicodeString::from("Hello, world !");
-        let formated = format!("{}", owned_unicode);
-        assert_eq!(formated,"Hello, world !");
+    fn test_resync_from_length() {
+        fn fake_api(out: &mut UNICODE_STRING, value: &str) {
+            let units: Vec<u16> = value.encode_utf16().collect();
+            assert!(units.len() * 2 <= out.MaximumLength as usize);
+            // SAFETY: `Buffer` is valid for `MaximumLength` bytes, which was checked above.
+            unsafe { core::ptr::copy_nonoverlapping(units.as_ptr(), out.Buffer, units.len()) };
+            out.Length = (units.len() * 2) as u16;
+        }
+
+        let mut my_string = OwnedUnicodeString::from(vec![0u16; 64]);
+        assert!(my_string.is_empty());
+        assert_eq!(my_string.maximum_length(), 128);
+        let buffer = my_string.as_ref().Buffer;
+
+        fake_api(my_string.as_mut_unicode_string(), r"\Device\HarddiskVolume1");
+        my_string.resync_from_length();
+
+        assert!(my_string == r"\Device\HarddiskVolume1");
+        assert_eq!(my_string.as_ref().Buffer, buffer);
+        assert!(my_string.capacity_code_units() >= 64);
+        assert!(my_string.debug_check_invariants());
     }
 
     #[test]
-    fn test_eq() {
-        let owned_unicode = OwnedUnicodeString::from("Hello, world !");
-        let same = OwnedUnicodeString::from("Hello, world !");
-        let result = owned_unicode == same;
-        assert_eq!(result,true)
+    #[should_panic(expected = "Length exceeds MaximumLength")]
+    fn test_resync_from_length_rejects_oversized_length() {
+        let mut my_string = OwnedUnicodeString::from(vec![0u16; 4]);
+        my_string.as_mut_unicode_string().Length = 10;
+        my_string.resync_from_length();
     }
 
     #[test]
-    fn test_add() {
-        let owned_unicode = OwnedUnicodeString::from("Hello, world !");
-        let other_str: &str = " Bye";
-        let other = OwnedUnicodeString::from(" !");
-        let expected1 = OwnedUnicodeString::from("Hello, world ! Bye");
-        let expected2 = OwnedUnicodeString::from("Hello, world ! Bye !");
-        let  concat1 =  owned_unicode + other_str;
-        let mut result = concat1 == expected1;
-        assert_eq!(result,true);
-        let  concat2 =  concat1  + other;
-        result = concat2 == expected2;
-        assert_eq!(result,true);
+    fn test_registry_path() {
+        let path = OwnedUnicodeString::registry_path(&["\\Registry", "Machine", "SYSTEM"]);
+        assert_eq!(format!("{}", path), r"\Registry\Machine\SYSTEM");
+
+        let messy = OwnedUnicodeString::registry_path(&["\\Registry\\", "", "\\\\Machine\\\\", "\\", "SOFTWARE\\\\Vendor"]);
+        assert_eq!(format!("{}", messy), r"\Registry\Machine\SOFTWARE\Vendor");
+
+        let relative = OwnedUnicodeString::registry_path(&["", "Services", "Tcpip\\"]);
+        assert_eq!(format!("{}", relative), r"Services\Tcpip");
+
+        assert!(OwnedUnicodeString::registry_path(&[]).is_empty());
+        assert!(OwnedUnicodeString::registry_path(&["", ""]).is_empty());
+        assert_eq!(format!("{}", OwnedUnicodeString::registry_path(&["\\\\"])), r"\");
     }
 
     #[test]
-    fn test_empty_string() {
-        let owned_unicode = OwnedUnicodeString::from("");
-        let expected = OwnedUnicodeString::from(Vec::new());
-        let  result = owned_unicode == expected;
-        assert_eq!(result, true);
+    fn test_join() {
+        let mut device = OwnedUnicodeString::from("Device");
+        device.as_null_terminated_slice();
+        let parts = [device, OwnedUnicodeString::from("Harddisk0"), OwnedUnicodeString::from("Partition1")];
+        let joined = OwnedUnicodeString::join(&parts, "\\");
+        assert_eq!(format!("{}", joined), r"Device\Harddisk0\Partition1");
+        assert!(joined.debug_check_invariants());
+
+        assert!(OwnedUnicodeString::join(&[], ", ").is_empty());
+        let single = OwnedUnicodeString::join(&parts[1..2], ", ");
+        assert!(single == "Harddisk0");
+        assert!(OwnedUnicodeString::join(&parts[..2], "") == "DeviceHarddisk0");
+
+        let long = [OwnedUnicodeString::from("a".repeat(20000).as_str()), OwnedUnicodeString::from("b".repeat(20000).as_str())];
+        assert_eq!(
+            OwnedUnicodeString::try_join(&long, "").err(),
+            Some(UnicodeStringError::TooLong { bytes: 80000 })
+        );
     }
 
+    #[cfg(feature = "unicode-case")]
     #[test]
-    fn test_unicode_characters() {
-        let unicode_str = "こんにちは"; // "Hello" in Japanese
-        let owned_unicode = OwnedUnicodeString::from(unicode_str);
-        let formated = format!("{}", owned_unicode);
-        assert_eq!(formated, unicode_str);
+    fn test_eq_ignore_case() {
+        let upper = OwnedUnicodeString::from("Σ");
+        assert!(upper.eq_ignore_case(&OwnedUnicodeString::from("σ")));
+        assert!(upper.eq_ignore_case(&OwnedUnicodeString::from("ς")));
+        assert!(OwnedUnicodeString::from("ΟΔΥΣΣΕΥΣ").eq_ignore_case(&OwnedUnicodeString::from("οδυσσευς")));
+        assert!(OwnedUnicodeString::from("Драйвер").eq_ignore_case(&OwnedUnicodeString::from("ДРАЙВЕР")));
+        assert!(OwnedUnicodeString::from("𐐀").eq_ignore_case(&OwnedUnicodeString::from("𐐨")));
+
+        let ascii = OwnedUnicodeString::from("\\Registry\\Machine\\SYSTEM");
+        assert!(ascii.eq_ignore_case(&OwnedUnicodeString::from("\\registry\\MACHINE\\system")));
+        assert!(!ascii.eq_ignore_case(&OwnedUnicodeString::from("\\registry\\machine\\software")));
+        assert!(!ascii.eq_ignore_case(&OwnedUnicodeString::from("\\registry\\machine\\system2")));
+
+        assert!(!OwnedUnicodeString::from("ß").eq_ignore_case(&OwnedUnicodeString::from("SS")));
+        let lone = OwnedUnicodeString::from_wide(&[0xD800]);
+        assert!(lone.eq_ignore_case(&OwnedUnicodeString::from_wide(&[0xD800])));
+        assert!(!lone.eq_ignore_case(&OwnedUnicodeString::from_wide(&[0xD801])));
+        assert!(!lone.eq_ignore_case(&OwnedUnicodeString::from("\u{FFFD}")));
     }
 
     #[test]
-    fn test_conversion_to_pcwstr_pwstr() {
-        let mut owned_unicode = OwnedUnicodeString::from("Hello, world!");
+    fn test_hex_dump() {
+        let mut my_string = OwnedUnicodeString::from("A");
+        my_string.ensure_is_null_terminated();
+        assert!(my_string.hex_dump().contains("0x0041"));
+        assert_eq!(my_string.hex_dump(), "0x0041 0x0000");
 
-        let pcwstr: PCWSTR = (&mut owned_unicode).into();
-        let pwstr: PWSTR = (&mut owned_unicode).into();
+        assert_eq!(OwnedUnicodeString::from_wide(&[0xD800, 0xABCD]).hex_dump(), "0xD800 0xABCD");
+        assert_eq!(OwnedUnicodeString::new().hex_dump(), "");
+    }
 
-        unsafe {
-            assert_eq!(*pcwstr, *pwstr);
-        }
+    #[test]
+    fn test_prepend() {
+        let mut path = OwnedUnicodeString::from("C:\\file");
+        path.as_null_terminated_slice();
+        path.prepend("\\??\\");
+        assert_eq!(format!("{}", path), r"\??\C:\file");
+        assert!(path.is_null_terminated());
+        assert!(path.debug_check_invariants());
 
-        assert!(owned_unicode.is_null_terminated());
+        let mut empty = OwnedUnicodeString::new();
+        empty.prepend("𐐷");
+        empty.prepend("");
+        assert_eq!(empty.as_slice(), &[0xD801, 0xDC37]);
     }
 
     #[test]
-    fn test_add_special_characters() {
-        let owned_unicode = OwnedUnicodeString::from("Line1\n");
-        let other = OwnedUnicodeString::from("Line2\tEnd");
-        let expected = OwnedUnicodeString::from("Line1\nLine2\tEnd");
+    fn test_from_string() {
+        let owned: alloc::string::String = format!("PID {}", 4);
+        let my_string = OwnedUnicodeString::from(owned);
+        assert_eq!(format!("{}", my_string), "PID 4");
+        assert_eq!(my_string.unicode_string.Length, 10);
 
-        let result = owned_unicode + other;
-        assert_eq!(result == expected, true);
+        let my_string: OwnedUnicodeString = alloc::string::String::from("𐐷x").into();
+        assert_eq!(my_string.as_slice(), &[0xD801, 0xDC37, 0x78]);
     }
 
     #[test]
-    fn test_buffer_overflow_protection() {
-        let mut owned_unicode = OwnedUnicodeString::from("Test");
+    fn test_from_char() {
+        let bmp = OwnedUnicodeString::from('A');
+        assert_eq!(bmp.unicode_string.Length, 2);
+        assert_eq!(bmp.as_slice(), &[0x41]);
 
-        // Manually extend the buffer to simulate potential overflow
-        owned_unicode.buffer.push(1);
+        let astral = OwnedUnicodeString::from('𐐷');
+        assert_eq!(astral.unicode_string.Length, 4);
+        assert_eq!(format!("{}", astral), "𐐷");
+    }
 
-        // Ensure the buffer still respects the max length
-        owned_unicode.compute_size();
-        assert!(owned_unicode.unicode_string.Length <= owned_unicode.unicode_string.MaximumLength);
+    #[test]
+    fn test_matches_count() {
+        assert_eq!(OwnedUnicodeString::from("ababab").matches_count("ab"), 3);
+        assert_eq!(OwnedUnicodeString::from("aaaa").matches_count("aa"), 2);
+        assert_eq!(OwnedUnicodeString::from("aaa").matches_count("aa"), 1);
+        assert_eq!(OwnedUnicodeString::from("abc").matches_count("x"), 0);
+        assert_eq!(OwnedUnicodeString::from("abc").matches_count(""), 0);
+        assert_eq!(OwnedUnicodeString::from("𐐷a𐐷").matches_count("𐐷"), 2);
+        assert_eq!(OwnedUnicodeString::new().matches_count("a"), 0);
     }
 
     #[test]
-    fn test_multiple_consecutive_null_characters() {
-        let mut owned_unicode = OwnedUnicodeString::from("Test");
+    fn test_unicode_string_builder() {
+        let volume = OwnedUnicodeString::from("HarddiskVolume1");
+        let mut builder = UnicodeStringBuilder::with_capacity(64);
+        let capacity = builder.buffer.capacity();
+        builder
+            .push_str("\\Device")
+            .push_char('\\')
+            .push_unicode(&volume)
+            .push_char('\\')
+            .push_str("Windows")
+            .push_char('𐐷');
+        assert_eq!(builder.len(), 33);
+        assert_eq!(builder.buffer.capacity(), capacity);
 
-        // Add multiple null characters
-        owned_unicode.buffer.extend(vec![0, 0, 0]);
+        let built = builder.build();
+        let added = OwnedUnicodeString::from("\\Device") + '\\' + volume + "\\" + "Windows" + '𐐷';
+        assert!(built == added);
+        assert!(built.debug_check_invariants());
 
-        owned_unicode.compute_size();
+        let empty = UnicodeStringBuilder::new();
+        assert!(empty.is_empty());
+        assert!(empty.build().is_empty());
 
-        // Check length is properly adjusted
-        let expected_length = (4 * size_of::<u16>()) as u16;
-        assert_eq!(owned_unicode.unicode_string.Length, expected_length);
+        let mut too_long = UnicodeStringBuilder::new();
+        too_long.push_str(&"a".repeat(40000));
+        assert!(too_long.try_build().is_err());
     }
 
     #[test]
-    fn test_large_input_handling() {
-        let large_string = "A".repeat(10000);
-        let owned_unicode = OwnedUnicodeString::from(large_string.as_str());
+    fn test_from_fragments() {
+        let fragments: &[&str] = &["Hello, ", "world", "!"];
+        let my_string = OwnedUnicodeString::from(fragments);
+        assert_eq!(format!("{}", my_string), "Hello, world!");
+        assert_eq!(my_string.buffer.capacity(), 13);
 
-        // Check the length is correctly calculated
-        assert_eq!(owned_unicode.unicode_string.Length, (10000 * size_of::<u16>()) as u16);
+        let no_fragments: &[&str] = &[];
+        assert!(OwnedUnicodeString::from(no_fragments).is_empty());
+        assert!(OwnedUnicodeString::from(&["", "𐐷", ""][..]) == "𐐷");
     }
 
     #[test]
-    fn test_equality_case_sensitivity() {
-        let upper_case = OwnedUnicodeString::from("HELLO");
-        let lower_case = OwnedUnicodeString::from("hello");
+    fn test_to_ascii_case() {
+        let original = OwnedUnicodeString::from("Straße Ünit 9z");
+        let upper = original.to_ascii_uppercase();
+        let lower = original.to_ascii_lowercase();
 
-        assert_ne!(upper_case == lower_case, true);
+        assert_eq!(format!("{}", upper), "STRAßE ÜNIT 9Z");
+        assert_eq!(format!("{}", lower), "straße Ünit 9z");
+        assert_eq!(format!("{}", original), "Straße Ünit 9z");
+        assert_eq!(upper.len(), original.len());
     }
 
     #[test]
-    fn test_fmt_invalid_utf16_sequence() {
-        let mut owned_unicode = OwnedUnicodeString::from("Hello");
+    fn test_trim_matches() {
+        let mut path = OwnedUnicodeString::from("path\\\\");
+        path.trim_end_matches("\\");
+        assert_eq!(format!("{}", path), "path");
 
-        // Manually add invalid UTF-16 sequence
-        owned_unicode.buffer.push(0xD800); // Half of a surrogate pair
-        owned_unicode.compute_size();
+        let mut prefixed = OwnedUnicodeString::from("\\\\\\??\\C:");
+        prefixed.as_null_terminated_slice();
+        prefixed.trim_start_matches("\\");
+        assert_eq!(format!("{}", prefixed), "??\\C:");
+        assert!(prefixed.is_null_terminated());
+        assert!(prefixed.debug_check_invariants());
 
-        let formated = format!("{}", owned_unicode);
-        assert_eq!(formated, "Hello�");
+        let mut repeated = OwnedUnicodeString::from("ababa");
+        repeated.trim_end_matches("ab");
+        assert_eq!(format!("{}", repeated), "ababa");
+        repeated.trim_start_matches("ab");
+        assert_eq!(format!("{}", repeated), "a");
+
+        let mut unchanged = OwnedUnicodeString::from("value");
+        unchanged.trim_end_matches("");
+        unchanged.trim_start_matches("");
+        assert_eq!(format!("{}", unchanged), "value");
+
+        let mut whole = OwnedUnicodeString::from("xyxy");
+        whole.trim_end_matches("xy");
+        assert!(whole.is_empty());
+        let mut whole = OwnedUnicodeString::from("xyxy");
+        whole.trim_start_matches("xy");
+        assert!(whole.is_empty());
+    }
+
+    #[test]
+    fn test_rsplit_once() {
+        let path = OwnedUnicodeString::from("\\Device\\HarddiskVolume1\\file.txt");
+        let (directory, file) = path.rsplit_once('\\').unwrap();
+        assert_eq!(format!("{}", directory), "\\Device\\HarddiskVolume1");
+        assert_eq!(format!("{}", file), "file.txt");
+
+        assert!(OwnedUnicodeString::from("file.txt").rsplit_once('\\').is_none());
+        assert!(OwnedUnicodeString::new().rsplit_once('\\').is_none());
+
+        let (directory, file) = OwnedUnicodeString::from("C:\\Windows\\").rsplit_once('\\').unwrap();
+        assert_eq!(format!("{}", directory), "C:\\Windows");
+        assert!(file.is_empty());
+
+        let (before, after) = OwnedUnicodeString::from("a𐐷b𐐷c").rsplit_once('𐐷').unwrap();
+        assert_eq!(format!("{} {}", before, after), "a𐐷b c");
+    }
+
+    #[test]
+    fn test_display_honors_width_and_alignment() {
+        let hi = OwnedUnicodeString::from("hi");
+        assert_eq!(format!("{:>5}", hi), "   hi");
+        assert_eq!(format!("{:<5}|", hi), "hi   |");
+        assert_eq!(format!("{:5}|", hi), "hi   |");
+        assert_eq!(format!("{:^6}", hi), "  hi  ");
+        assert_eq!(format!("{:-^5}", hi), "-hi--");
+        assert_eq!(format!("{:1}", hi), "hi");
+        assert_eq!(format!("{:.1}", hi), "h");
+
+        // Width counts scalar values, so a surrogate pair is one column.
+        assert_eq!(format!("{:>3}", OwnedUnicodeString::from("𐐷")), "  𐐷");
+        assert_eq!(format!("{:>2}", OwnedUnicodeString::from_wide(&[0xD800])), " �");
+    }
+
+    #[test]
+    fn test_from_slice_until_nul() {
+        let mut field = [0xFFFFu16; 16];
+        field[..4].copy_from_slice(&[0x4E, 0x55, 0x4C, 0]);
+        let early = OwnedUnicodeString::from_slice_until_nul(&field);
+        assert_eq!(format!("{}", early), "NUL");
+        assert_eq!(early.buffer.len(), 3);
+
+        let full = [0x41u16, 0x42, 0x43];
+        assert_eq!(OwnedUnicodeString::from_slice_until_nul(&full).as_slice(), &full);
+        assert!(OwnedUnicodeString::from_slice_until_nul(&[0, 0x41]).is_empty());
+        assert!(OwnedUnicodeString::from_slice_until_nul(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_char_at() {
+        let my_string = OwnedUnicodeString::from("a\u{10437}b");
+        assert_eq!(my_string.len(), 4);
+        assert_eq!(my_string.char_at(0), Some('a'));
+        assert_eq!(my_string.char_at(1), Some('\u{10437}'));
+        assert_eq!(my_string.char_at(2), Some('b'));
+        assert_eq!(my_string.char_at(3), None);
+
+        let lone = OwnedUnicodeString::from_wide(&[0xD800, 0x63]);
+        assert_eq!(lone.char_at(0), Some(char::REPLACEMENT_CHARACTER));
+        assert_eq!(lone.char_at(1), Some('c'));
+        assert_eq!(OwnedUnicodeString::new().char_at(0), None);
+    }
+
+    #[test]
+    fn test_is_ascii() {
+        assert!(OwnedUnicodeString::from("HarddiskVolume1").is_ascii());
+        assert!(OwnedUnicodeString::new().is_ascii());
+        assert!(!OwnedUnicodeString::from("café").is_ascii());
+        assert!(!OwnedUnicodeString::from("𐐷").is_ascii());
+
+        let mut terminated = OwnedUnicodeString::from("abc");
+        terminated.as_null_terminated_slice();
+        assert!(terminated.is_ascii());
+    }
+
+    #[test]
+    fn test_to_ascii_string() {
+        let ascii = OwnedUnicodeString::from("\\Device\\Null");
+        assert_eq!(ascii.to_ascii_string(), Some(alloc::string::String::from("\\Device\\Null")));
+        assert_eq!(OwnedUnicodeString::new().to_ascii_string(), Some(alloc::string::String::new()));
+        assert_eq!(OwnedUnicodeString::from("naïve").to_ascii_string(), None);
+        assert_eq!(OwnedUnicodeString::from_wide(&[0x41, 0xD800]).to_ascii_string(), None);
+    }
+
+    #[test]
+    fn test_replace_invalid() {
+        let mut my_string = OwnedUnicodeString::from_wide(&[0x61, 0xD800, 0x62, 0xD801, 0xDC37, 0xDFFF]);
+        my_string.as_null_terminated_slice();
+        my_string.replace_invalid('\u{FFFD}');
+        assert_eq!(my_string.as_slice(), &[0x61, 0xFFFD, 0x62, 0xD801, 0xDC37, 0xFFFD]);
+        assert!(my_string.try_chars().all(|result| result.is_ok()));
+        assert!(my_string.is_null_terminated());
+        assert!(my_string.debug_check_invariants());
+
+        let mut astral = OwnedUnicodeString::from_wide(&[0xD800, 0x41]);
+        astral.replace_invalid('𐐷');
+        assert_eq!(astral.len(), 3);
+        assert_eq!(format!("{}", astral), "𐐷A");
+
+        let mut valid = OwnedUnicodeString::from("fine");
+        valid.replace_invalid('?');
+        assert_eq!(format!("{}", valid), "fine");
+    }
+
+    #[test]
+    fn test_keep_null_terminator() {
+        let mut my_string = OwnedUnicodeString::new_terminated("abc");
+        assert!(my_string.keeps_null_terminator());
+        assert!(my_string.maximum_length() >= my_string.length() + 2);
+
+        my_string.push_str("def");
+        assert_eq!(my_string.length(), 12);
+        assert!(my_string.maximum_length() >= my_string.length() + 2);
+
+        my_string.trim_nul();
+        assert_eq!(my_string.maximum_length(), my_string.length() + 2);
+
+        let buffer_len = my_string.buffer.len();
+        let pointer = PCWSTR::from(&mut my_string);
+        assert_eq!(my_string.buffer.len(), buffer_len);
+        assert_eq!(pointer, my_string.buffer.as_ptr());
+
+        my_string.remove_range(0, 6);
+        assert!(my_string.is_empty());
+        assert_eq!(my_string.maximum_length(), 2);
+        assert!(my_string.debug_check_invariants());
+
+        assert!(!OwnedUnicodeString::from("abc").keeps_null_terminator());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_keep_null_terminator_rejects_full_buffer() {
+        let mut my_string = OwnedUnicodeString::from(vec![0x41u16; u16::MAX as usize / 2]);
+        my_string.keep_null_terminator();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_keep_null_terminator_try_growth_at_limit() {
+        let base = "a".repeat(32000);
+
+        let fits = OwnedUnicodeString::new_terminated(&base).try_concat(&"b".repeat(766).as_str().into()).unwrap();
+        assert_eq!(fits.len(), MAX_CODE_UNITS - 1);
+        assert_eq!(fits.maximum_length() as usize, MAX_CODE_UNITS * 2);
+        assert!(fits.as_pcwstr().is_some());
+
+        let overflow = OwnedUnicodeString::new_terminated(&base).try_concat(&"b".repeat(767).as_str().into());
+        assert_eq!(overflow.err(), Some(UnicodeStringError::TooLong { bytes: 65536 }));
+
+        let mut inserted = OwnedUnicodeString::new_terminated(&base);
+        assert_eq!(
+            inserted.try_insert_str(0, &"b".repeat(767)),
+            Err(UnicodeStringError::TooLong { bytes: 65536 })
+        );
+        assert_eq!(inserted.len(), 32000);
+        inserted.try_insert_str(0, &"b".repeat(766)).unwrap();
+        assert_eq!(inserted.len(), MAX_CODE_UNITS - 1);
+        assert!(inserted.as_pcwstr().is_some());
+    }
+
+    #[test]
+    fn test_lines() {
+        let text = OwnedUnicodeString::from("one\r\ntwo\nthree\rfour\n\nsix\r\n");
+        let lines: Vec<alloc::string::String> = text.lines().map(|line| format!("{}", line)).collect();
+        assert_eq!(lines, vec!["one", "two", "three", "four", "", "six"]);
+
+        let no_newline: Vec<OwnedUnicodeString> = OwnedUnicodeString::from("single").lines().collect();
+        assert_eq!(no_newline.len(), 1);
+        assert_eq!(OwnedUnicodeString::new().lines().count(), 0);
+        assert_eq!(OwnedUnicodeString::from("\n").lines().count(), 1);
+        assert_eq!(OwnedUnicodeString::from("\r\r\n").lines().count(), 2);
+        assert_eq!(OwnedUnicodeString::from("a\n\r").lines().count(), 2);
+    }
+
+    #[test]
+    fn test_normalize_separators() {
+        let mut path = OwnedUnicodeString::from("C:/Users/foo");
+        let pointer = path.as_ref().Buffer;
+        path.normalize_separators();
+        assert_eq!(format!("{}", path), "C:\\Users\\foo");
+        assert_eq!(path.as_ref().Buffer, pointer);
+
+        let mut mixed = OwnedUnicodeString::from("\\??/C:\\a//b");
+        mixed.normalize_separators();
+        assert_eq!(format!("{}", mixed), "\\??\\C:\\a\\\\b");
+    }
+
+    #[test]
+    fn test_from_utf8() {
+        let valid = OwnedUnicodeString::from_utf8("\\Device\\Null 𐐷".as_bytes()).unwrap();
+        assert!(valid == "\\Device\\Null 𐐷");
+
+        assert_eq!(
+            OwnedUnicodeString::from_utf8(&[0x61, 0xC3]).err(),
+            Some(UnicodeStringError::InvalidUtf8 { index: 1 })
+        );
+        assert_eq!(
+            OwnedUnicodeString::from_utf8(&[0xFF]).err(),
+            Some(UnicodeStringError::InvalidUtf8 { index: 0 })
+        );
+        assert!(OwnedUnicodeString::from_utf8(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_utf8_lossy() {
+        let lossy = OwnedUnicodeString::from_utf8_lossy(b"Hello \xF0\x90\x80World\xFF");
+        assert_eq!(
+            format!("{}", lossy),
+            alloc::string::String::from_utf8_lossy(b"Hello \xF0\x90\x80World\xFF")
+        );
+        assert_eq!(format!("{}", lossy), "Hello \u{FFFD}World\u{FFFD}");
+        assert!(OwnedUnicodeString::from_utf8_lossy("ok".as_bytes()) == "ok");
+    }
+
+    #[test]
+    fn test_append_wide() {
+        let mut my_string = OwnedUnicodeString::from("xy");
+        my_string.as_null_terminated_slice();
+        my_string.append_wide(&[0x0041, 0x0042]);
+        assert_eq!(my_string.as_slice(), &[0x78, 0x79, 0x41, 0x42]);
+        assert!(my_string.debug_check_invariants());
+
+        my_string.append_wide(&[]);
+        my_string.append_wide(&[0x43, 0]);
+        assert_eq!(format!("{}", my_string), "xyABC");
+        assert!(my_string.is_null_terminated());
+    }
+
+    #[test]
+    fn test_from_str_truncated() {
+        let oversized = "a".repeat(40000);
+        let truncated = OwnedUnicodeString::from_str_truncated(&oversized);
+        assert_eq!(truncated.len(), 32766);
+        assert!(truncated.as_slice().iter().all(|&unit| unit == 0x61));
+
+        // The surrogate pair that would straddle the limit is dropped as a whole.
+        let straddling = alloc::format!("{}𐐷tail", "b".repeat(32765));
+        let truncated = OwnedUnicodeString::from_str_truncated(&straddling);
+        assert_eq!(truncated.len(), 32765);
+        assert!(truncated.try_chars().all(|result| result.is_ok()));
+
+        let mut terminated = truncated;
+        assert_eq!(terminated.as_null_terminated_slice().len(), 32766);
+        assert!(terminated.debug_check_invariants());
+
+        assert!(OwnedUnicodeString::from_str_truncated("short") == "short");
+    }
+
+    #[test]
+    fn test_compare_with_str() {
+        let abc = OwnedUnicodeString::from("abc");
+        assert!(abc == *"abc");
+        assert!(abc == "abc");
+        assert!(abc != "abcd");
+        assert!(abc < *"abd");
+        assert!(abc > *"abb");
+        assert!(abc > "ab");
+        assert!(abc <= "abc");
+
+        // Ordering follows code units, so a supplementary character sorts before U+FFFD.
+        let supplementary = OwnedUnicodeString::from("𐐷");
+        assert!(supplementary < "\u{FFFD}");
+
+        let terminated = OwnedUnicodeString::from(vec![0x61, 0x62, 0x63, 0]);
+        assert!(terminated == "abc");
+    }
+
+    #[test]
+    fn test_from_wide_ptr_len() {
+        let wide: Vec<u16> = "\\Device\\Null".encode_utf16().collect();
+        let my_string = unsafe { OwnedUnicodeString::from_wide_ptr_len(wide.as_ptr(), 7) };
+        assert!(my_string == "\\Device");
+        assert_eq!(my_string.as_slice(), &wide[..7]);
+
+        let offset = unsafe { OwnedUnicodeString::from_wide_ptr_len(wide.as_ptr().add(8), 4) };
+        assert!(offset == "Null");
+
+        let empty = unsafe { OwnedUnicodeString::from_wide_ptr_len(core::ptr::null(), 0) };
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_into_raw_from_raw() {
+        let mut original = OwnedUnicodeString::with_capacity(64);
+        original.push_str("\\Registry\\Machine");
+        let raw = original.into_raw();
+        assert_eq!(raw.Length, 34);
+        assert_eq!(raw.MaximumLength, 34);
+        let units = unsafe { slice::from_raw_parts(raw.Buffer, raw.Length as usize / 2) };
+        assert_eq!(units, "\\Registry\\Machine".encode_utf16().collect::<Vec<u16>>().as_slice());
+
+        let mut reclaimed = unsafe { OwnedUnicodeString::from_raw(raw) };
+        assert!(reclaimed == "\\Registry\\Machine");
+        assert!(reclaimed.debug_check_invariants());
+        reclaimed.push_str("\\SYSTEM");
+        assert!(reclaimed == "\\Registry\\Machine\\SYSTEM");
+
+        let terminated = OwnedUnicodeString::new_terminated("a");
+        let raw = terminated.into_raw();
+        assert_eq!((raw.Length, raw.MaximumLength), (2, 4));
+        assert!(unsafe { OwnedUnicodeString::from_raw(raw) } == "a");
+
+        let mut secret = OwnedUnicodeString::new_secret("pw");
+        secret.reserve(16);
+        let reclaimed = unsafe { OwnedUnicodeString::from_raw(secret.into_raw()) };
+        assert!(reclaimed == "pw");
+
+        let empty = unsafe { OwnedUnicodeString::from_raw(OwnedUnicodeString::new().into_raw()) };
+        assert!(empty.is_empty());
+
+        // The terminator added past a full `MaximumLength` is not part of the leaked allocation.
+        let mut full = OwnedUnicodeString::from(vec![0x41u16; MAX_CODE_UNITS]);
+        assert_eq!(full.as_null_terminated_slice().len(), MAX_CODE_UNITS + 1);
+        let raw = full.into_raw();
+        assert_eq!((raw.Length, raw.MaximumLength), (65534, 65534));
+        let reclaimed = unsafe { OwnedUnicodeString::from_raw(raw) };
+        assert_eq!(reclaimed.len(), MAX_CODE_UNITS);
+        assert_eq!(reclaimed.capacity_code_units(), MAX_CODE_UNITS);
+    }
+
+    #[test]
+    fn test_trailing_nul_count() {
+        let mut owned_unicode = OwnedUnicodeString::from(vec![0x0041, 0x0000, 0x0042, 0x0000, 0x0000, 0x0000]);
+        assert_eq!(owned_unicode.trailing_nul_count(), 3);
+        assert_eq!(owned_unicode.len(), 3);
+        assert_eq!(
+            owned_unicode.maximum_length() - owned_unicode.length(),
+            (owned_unicode.trailing_nul_count() * 2) as u16
+        );
+
+        owned_unicode.trim_nul();
+        assert_eq!(owned_unicode.trailing_nul_count(), 0);
+
+        assert_eq!(OwnedUnicodeString::new().trailing_nul_count(), 0);
+    }
+
+    #[test]
+    fn test_display_alternate() {
+        let owned_unicode = OwnedUnicodeString::from("a\tb\nc\r\u{1}\"é");
+        assert_eq!(format!("{}", owned_unicode), "a\tb\nc\r\u{1}\"é");
+        assert_eq!(format!("{:#}", owned_unicode), "«a\\tb\\nc\\r\\u{1}\"é»");
+        assert_eq!(format!("{:#}", OwnedUnicodeString::new()), "«»");
+
+        let invalid = OwnedUnicodeString::from(vec![0x0061, 0xD800]);
+        assert_eq!(format!("{:#}", invalid), "«a\u{FFFD}»");
+    }
+
+    #[test]
+    fn test_nt_object_win32_extended() {
+        let nt_path = OwnedUnicodeString::from(r"\??\C:\foo");
+        let win32_path = nt_path.to_win32_extended();
+        assert!(win32_path == r"\\?\C:\foo");
+        assert!(win32_path.to_nt_object() == r"\??\C:\foo");
+        assert!(win32_path.to_nt_object().to_win32_extended() == win32_path);
+
+        // Without the expected prefix both conversions return an unchanged copy.
+        assert!(nt_path.to_nt_object() == nt_path);
+        assert!(win32_path.to_win32_extended() == win32_path);
+        assert!(OwnedUnicodeString::from(r"C:\foo").to_win32_extended() == r"C:\foo");
+        assert!(OwnedUnicodeString::from(r"\Device\Null").to_nt_object() == r"\Device\Null");
+        assert!(OwnedUnicodeString::new().to_nt_object().is_empty());
+    }
+
+    #[test]
+    fn test_as_pcwstr() {
+        let mut owned_unicode = OwnedUnicodeString::from("Hi");
+        assert!(owned_unicode.as_pcwstr().is_none());
+
+        let _: PCWSTR = (&mut owned_unicode).into();
+        let pcwstr = owned_unicode.as_pcwstr().unwrap();
+        assert!(core::ptr::eq(pcwstr, AsRef::<UNICODE_STRING>::as_ref(&owned_unicode).Buffer));
+        assert!(unsafe { OwnedUnicodeString::from_pcwstr(pcwstr) } == "Hi");
+
+        let terminated = OwnedUnicodeString::new_terminated("");
+        assert_eq!(unsafe { *terminated.as_pcwstr().unwrap() }, 0);
+        assert!(OwnedUnicodeString::new().as_pcwstr().is_none());
+    }
+
+    #[test]
+    fn test_max_code_units() {
+        assert_eq!(MAX_BYTES, 65535);
+        assert_eq!(MAX_CODE_UNITS, 32767);
+
+        let below = OwnedUnicodeString::try_from_vec(vec![0x41; MAX_CODE_UNITS - 1]).unwrap();
+        assert_eq!(below.len(), MAX_CODE_UNITS - 1);
+        let at = OwnedUnicodeString::try_from_vec(vec![0x41; MAX_CODE_UNITS]).unwrap();
+        assert_eq!(at.maximum_length() as usize, MAX_CODE_UNITS * 2);
+
+        assert_eq!(
+            OwnedUnicodeString::try_from_vec(vec![0x41; MAX_CODE_UNITS + 1]).err(),
+            Some(UnicodeStringError::TooLong { bytes: (MAX_CODE_UNITS + 1) * 2 })
+        );
+        assert!(UnicodeStringError::TooLong { bytes: 65536 }.to_string().contains("65535"));
+    }
+
+    #[cfg(feature = "width")]
+    #[test]
+    fn test_display_width() {
+        assert_eq!(OwnedUnicodeString::from("ab").display_width(), 2);
+        assert_eq!(OwnedUnicodeString::from("你好").display_width(), 4);
+        assert_eq!(OwnedUnicodeString::from("ｶﾀｶﾅ").display_width(), 4);
+        assert_eq!(OwnedUnicodeString::from("ＡＢ").display_width(), 4);
+        assert_eq!(OwnedUnicodeString::from("é😀𠀋").display_width(), 5);
+        assert_eq!(OwnedUnicodeString::from(vec![0x0061, 0xD800]).display_width(), 2);
+        assert_eq!(OwnedUnicodeString::new().display_width(), 0);
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let mut owned_unicode = OwnedUnicodeString::from("\\Device");
+        owned_unicode.try_reserve(100).unwrap();
+        assert!(owned_unicode.capacity_code_units() >= owned_unicode.len() + 100);
+        assert!(owned_unicode.debug_check_invariants());
+        owned_unicode.push_str("\\Null");
+        assert!(owned_unicode == "\\Device\\Null");
+
+        assert_eq!(owned_unicode.try_reserve(usize::MAX), Err(UnicodeStringError::AllocFailed));
+        assert!(owned_unicode == "\\Device\\Null");
+
+        let mut secret = OwnedUnicodeString::new_secret("pw");
+        secret.try_reserve(50).unwrap();
+        assert!(secret.capacity_code_units() >= 52);
+        assert!(secret.debug_check_invariants());
+        assert_eq!(secret.try_reserve(usize::MAX), Err(UnicodeStringError::AllocFailed));
+        assert!(secret == "pw");
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        let owned_unicode = OwnedUnicodeString::try_from_str("\\Registry\\😀").unwrap();
+        assert!(owned_unicode == "\\Registry\\😀");
+        assert_eq!(owned_unicode.capacity_code_units(), owned_unicode.len());
+        assert!(owned_unicode.debug_check_invariants());
+
+        assert!(OwnedUnicodeString::try_from_str("").unwrap().is_empty());
+        assert!(OwnedUnicodeString::try_from_str("a\0").unwrap() == "a");
+
+        // An out-of-memory condition cannot be provoked reliably here; it surfaces as `AllocFailed` through the
+        // same `try_reserve` path that `test_try_reserve` exercises with an overflowing request.
+        let at_limit = "a".repeat(MAX_CODE_UNITS);
+        assert_eq!(OwnedUnicodeString::try_from_str(&at_limit).unwrap().len(), MAX_CODE_UNITS);
+        assert_eq!(
+            OwnedUnicodeString::try_from_str(&"😀".repeat(20000)).err(),
+            Some(UnicodeStringError::TooLong { bytes: 80000 })
+        );
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut in_progress = OwnedUnicodeString::from("\\Registry\\Mach");
+        let mut finished = OwnedUnicodeString::new_secret("\\Device\\Null");
+        in_progress.swap(&mut finished);
+
+        assert!(in_progress == "\\Device\\Null");
+        assert!(in_progress.is_secret());
+        assert!(finished == "\\Registry\\Mach");
+        assert!(!finished.is_secret());
+
+        in_progress.push_str("\\Sub");
+        finished.push_str("ine\\SYSTEM".repeat(8).as_str());
+        assert!(in_progress == "\\Device\\Null\\Sub");
+        assert!(finished.starts_with("\\Registry\\Machine\\SYSTEM"));
+        assert!(in_progress.debug_check_invariants());
+        assert!(finished.debug_check_invariants());
+        let pcwstr: PCWSTR = (&mut finished).into();
+        assert!(unsafe { OwnedUnicodeString::from_pcwstr(pcwstr) } == finished);
+    }
+
+    #[test]
+    fn test_pad_to() {
+        let mut owned_unicode = OwnedUnicodeString::from("ab");
+        owned_unicode.pad_to(5, ' ');
+        assert!(owned_unicode == "ab   ");
+        assert_eq!(owned_unicode.len(), 5);
+        assert!(owned_unicode.debug_check_invariants());
+
+        owned_unicode.pad_to(3, '*');
+        assert!(owned_unicode == "ab   ");
+
+        let mut astral = OwnedUnicodeString::from("a");
+        astral.pad_to(4, '𐐷');
+        assert!(astral == "a𐐷");
+        astral.pad_to(5, '𐐷');
+        assert!(astral == "a𐐷𐐷");
+
+        let mut terminated = OwnedUnicodeString::new_terminated("x");
+        terminated.pad_to(3, '0');
+        assert!(terminated == "x00");
+        assert!(terminated.as_pcwstr().is_some());
+    }
+
+    #[test]
+    fn test_from_guid() {
+        let guid = GUID {
+            data1: 0x4D36E972,
+            data2: 0xE325,
+            data3: 0x11CE,
+            data4: [0xBF, 0xC1, 0x08, 0x00, 0x2B, 0xE1, 0x03, 0x18],
+        };
+        let owned_unicode = OwnedUnicodeString::from_guid(&guid);
+        assert!(owned_unicode == "{4D36E972-E325-11CE-BFC1-08002BE10318}");
+        assert_eq!(owned_unicode.len(), 38);
+        assert_eq!(owned_unicode.capacity_code_units(), 38);
+
+        let zero = OwnedUnicodeString::from_guid(&GUID::from_u128(0));
+        assert!(zero == "{00000000-0000-0000-0000-000000000000}");
+        let path = OwnedUnicodeString::from("\\Registry\\Machine\\SYSTEM\\CurrentControlSet\\Control\\Class\\")
+            + OwnedUnicodeString::from_guid(&GUID::from_u128(u128::MAX));
+        assert!(path.ends_with("\\{FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF}"));
+    }
+
+    #[test]
+    fn test_utf16_display() {
+        let text = "\\Device\\こんにちは é 😀\n";
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let owned_unicode = OwnedUnicodeString::from(text);
+        assert_eq!(format!("{}", Utf16Display(&units)), format!("{}", owned_unicode));
+        assert_eq!(format!("{:#}", Utf16Display(&units)), format!("{:#}", owned_unicode));
+        assert_eq!(format!("[{:^30.9}]", Utf16Display(&units)), format!("[{:^30.9}]", owned_unicode));
+
+        let invalid = [0x0061, 0xDC00, 0x0062];
+        assert_eq!(format!("{}", Utf16Display(&invalid)), "a\u{FFFD}b");
+        assert_eq!(format!("{}", Utf16Display(&[0x0061, 0x0000])), "a\0");
+        assert_eq!(format!("{}", Utf16Display(&[])), "");
+    }
+
+    #[cfg(all(windows, feature = "nt-rtl"))]
+    #[test]
+    fn test_nt_equals() {
+        let upper = OwnedUnicodeString::from("FOO");
+        let lower = OwnedUnicodeString::from("foo");
+        assert!(upper.nt_equals(&lower, true));
+        assert!(!upper.nt_equals(&lower, false));
+        assert!(upper.nt_equals(&OwnedUnicodeString::from(vec![0x46, 0x4F, 0x4F, 0]), false));
+        assert!(!upper.nt_equals(&OwnedUnicodeString::from("FOOD"), true));
+    }
+
+    #[test]
+    fn test_from_ansi() {
+        let owned_unicode = OwnedUnicodeString::from_ansi(b"Hello");
+        assert_eq!(owned_unicode.as_slice(), &[0x0048, 0x0065, 0x006C, 0x006C, 0x006F]);
+
+        let latin1 = OwnedUnicodeString::from_ansi(&[0x45, 0xE9, 0xFF, 0x80]);
+        assert_eq!(latin1.as_slice(), &[0x0045, 0x00E9, 0x00FF, 0x0080]);
+        assert!(latin1 == "Eéÿ\u{80}");
+
+        let ansi = OwnedAnsiString::from("Tcpip\0");
+        assert!(OwnedUnicodeString::from_ansi(ansi.as_slice()) == "Tcpip");
+        assert!(OwnedUnicodeString::from_ansi(b"").is_empty());
+    }
+
+    #[test]
+    fn test_all_chars_any_char() {
+        let allowed = |c: char| c.is_alphanumeric() || c == '\\';
+        let forbidden = |c: char| matches!(c, '<' | '>' | ':' | '"' | '/' | '|' | '?' | '*');
+
+        let path = OwnedUnicodeString::from("\\Registry\\Machine\\Überblick");
+        assert!(path.all_chars(allowed));
+        assert!(!path.any_char(forbidden));
+
+        let name = OwnedUnicodeString::from("report*.log");
+        assert!(!name.all_chars(allowed));
+        assert!(name.any_char(forbidden));
+
+        let invalid = OwnedUnicodeString::from(vec![0x0061, 0xD800]);
+        assert!(!invalid.all_chars(allowed));
+        assert!(invalid.any_char(|c| c == char::REPLACEMENT_CHARACTER));
+
+        let mut visited = 0;
+        assert!(OwnedUnicodeString::from("a?bc").any_char(|c| {
+            visited += 1;
+            c == '?'
+        }));
+        assert_eq!(visited, 2);
+        assert!(OwnedUnicodeString::new().all_chars(|_| false));
+        assert!(!OwnedUnicodeString::new().any_char(|_| true));
+    }
+
+    #[cfg(feature = "unicode-case")]
+    #[test]
+    fn test_to_lowercase_uppercase_full() {
+        let greek = OwnedUnicodeString::from("ΑΒΓ ΣΣ");
+        assert!(greek.to_lowercase_full() == "αβγ σσ");
+        assert!(greek.to_lowercase_full().to_uppercase_full() == greek);
+        assert!(OwnedUnicodeString::from("ς").to_uppercase_full() == "Σ");
+
+        let cyrillic = OwnedUnicodeString::from("Привет, МИР");
+        assert!(cyrillic.to_lowercase_full() == "привет, мир");
+        assert!(cyrillic.to_uppercase_full() == "ПРИВЕТ, МИР");
+
+        let ascii = OwnedUnicodeString::from("\\Device\\HarddiskVolume1");
+        assert!(ascii.to_lowercase_full() == ascii.to_ascii_lowercase());
+        assert!(ascii.to_uppercase_full() == ascii.to_ascii_uppercase());
+
+        // Simple mappings only: no special casing, and the length in code units is preserved.
+        assert!(OwnedUnicodeString::from("ß").to_uppercase_full() == "ß");
+        assert!(OwnedUnicodeString::from("İ").to_lowercase_full() == "i");
+        assert!(OwnedUnicodeString::from("ᾳ").to_uppercase_full() == "ᾼ");
+        assert!(OwnedUnicodeString::from("𐐷").to_uppercase_full() == "𐐏");
+
+        let invalid = OwnedUnicodeString::from(vec![0x0041, 0xD800, 0x0042]);
+        assert_eq!(invalid.to_lowercase_full().as_slice(), &[0x0061, 0xD800, 0x0062]);
+    }
+}